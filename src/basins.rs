@@ -0,0 +1,196 @@
+use crate::{Cells, Settings, Storage};
+
+/// The state-transition graph of a rule on a fixed number of cells, mapping each of the 2^N configurations
+/// to its successor. 
+///
+/// Configurations are identified by their state number, the binary number formed by the cells with the
+/// leftmost cell as the most significant bit. 
+///
+/// ```
+/// use eca_explorer::{EdgeHandling, Rule, Settings, StateGraph};
+///
+/// let graph = StateGraph::new(4, &Settings::new(Rule(90), EdgeHandling::Wrap)).unwrap();
+/// assert_eq!(graph.len(), 16);
+/// // the neighbours of the single live cell become alive
+/// assert_eq!(graph.successor(0b0100), 0b1010);
+///
+/// let basins = graph.basins();
+/// // rule 90 on 4 cells sends everything to the dead configuration
+/// assert_eq!(basins.attractors.len(), 1);
+/// assert_eq!(basins.attractors[0].cycle, vec![0]);
+/// assert_eq!(basins.attractors[0].max_transient, 2);
+/// assert_eq!(graph.gardens_of_eden().count(), 12);
+/// ```
+#[derive(Clone, Debug)]
+pub struct StateGraph {
+    width: u16, 
+    successors: Vec<u32>, 
+}
+
+/// The states flowing into a single attractor cycle. 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attractor {
+    /// The states of the cycle, starting with the smallest state number. 
+    pub cycle: Vec<u32>, 
+    /// Number of states in the basin, including the cycle itself. 
+    pub size: usize, 
+    /// Largest number of generations any state in the basin takes to reach the cycle. 
+    pub max_transient: u32, 
+}
+
+/// The basins of attraction of a [`StateGraph`]. 
+#[derive(Clone, Debug)]
+pub struct Basins {
+    /// All attractors, ordered by the smallest state in each basin. 
+    pub attractors: Vec<Attractor>, 
+    /// Index into `attractors` of the basin containing each state. 
+    pub basin: Vec<u32>, 
+    /// Number of generations each state takes to reach its attractor cycle. 
+    pub transient: Vec<u32>, 
+}
+
+impl StateGraph {
+    /// Largest number of cells supported, limiting the graph to 2^24 states. 
+    pub const MAX_WIDTH: u16 = 24;
+
+    /// Computes the successor of every configuration of `width` cells using [`Storage::step_into`]. 
+    pub fn new(width: u16, settings: &Settings) -> Result<StateGraph, &'static str> {
+        if !(3..=StateGraph::MAX_WIDTH).contains(&width) {
+            return Err("Width of the state-transition graph must be between 3 and 24 cells")
+        }
+        if settings.rule.states() != 2 {
+            return Err("State-transition graphs are only computed for rules with two states")
+        }
+        if settings.stochastic.is_some() || settings.update.is_random() {
+            return Err("State-transition graphs are only computed for deterministic rules")
+        }
+        if settings.second_order {
+            return Err("State-transition graphs are only computed for first-order rules")
+        }
+        let mut graph = StateGraph {
+            width, 
+            successors: Vec::new(), 
+        };
+        let [mut front, mut back] = [0, 0].map(|state| graph.cells(state));
+        graph.successors = (0..1 << width)
+            .map(|state| {
+                graph.fill(state, &mut front);
+                front.step_into(&mut back, settings);
+                graph.state(&back)
+            })
+            .collect();
+        Ok(graph)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Number of states, i.e. 2^width. 
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    /// Always false, since there are at least 8 states. 
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    pub fn successor(&self, state: u32) -> u32 {
+        self.successors[state as usize]
+    }
+
+    /// The configuration with the given state number. 
+    pub fn cells(&self, state: u32) -> Cells {
+        let mut cells = Cells(Vec::with_capacity(self.width.into()));
+        self.fill(state, &mut cells);
+        cells
+    }
+
+    /// The state number of a configuration of `width` cells. 
+    pub fn state(&self, cells: &Cells) -> u32 {
+        assert_eq!(cells.0.len(), self.width as usize);
+        cells.0
+            .iter()
+            .fold(0, |state, &cell| state << 1 | cell as u32)
+    }
+
+    /// Number of predecessors of each state. 
+    pub fn in_degrees(&self) -> Vec<u32> {
+        let mut degrees = vec![0; self.len()];
+        for &successor in &self.successors {
+            degrees[successor as usize] += 1;
+        }
+        degrees
+    }
+
+    /// The Garden-of-Eden states, which have no predecessor and can only occur as initial configurations. 
+    pub fn gardens_of_eden(&self) -> impl Iterator<Item = u32> {
+        self.in_degrees()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, degree)| degree == 0)
+            .map(|(state, _)| state as u32)
+    }
+
+    /// Partitions the states into basins of attraction. Every state eventually reaches exactly one cycle. 
+    pub fn basins(&self) -> Basins {
+        const UNVISITED: u32 = u32::MAX;
+        // states on the path currently being followed are marked by this basin until it's known
+        const ON_PATH: u32 = u32::MAX - 1;
+
+        let mut attractors: Vec<Attractor> = Vec::new();
+        let mut basin = vec![UNVISITED; self.len()];
+        let mut transient = vec![0; self.len()];
+        let mut path = Vec::new();
+
+        for start in 0..self.len() as u32 {
+            // follow successors until reaching a state with a known basin, or closing a new cycle
+            let mut state = start;
+            while basin[state as usize] == UNVISITED {
+                basin[state as usize] = ON_PATH;
+                path.push(state);
+                state = self.successor(state);
+            }
+            if basin[state as usize] == ON_PATH {
+                let index = path.iter().position(|&on_path| on_path == state).unwrap();
+                let mut cycle = path.split_off(index);
+                for &on_cycle in &cycle {
+                    basin[on_cycle as usize] = attractors.len() as u32;
+                }
+                let smallest = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+                cycle.rotate_left(smallest);
+                attractors.push(Attractor {
+                    cycle, 
+                    size: 0, 
+                    max_transient: 0, 
+                });
+            }
+            // the path leads into the basin of `state`, one generation further away at each step back
+            let [index, mut distance] = [basin[state as usize], transient[state as usize]];
+            for &on_path in path.iter().rev() {
+                distance += 1;
+                basin[on_path as usize] = index;
+                transient[on_path as usize] = distance;
+            }
+            path.clear();
+        }
+
+        for (&index, &transient) in basin.iter().zip(&transient) {
+            let attractor = &mut attractors[index as usize];
+            attractor.size += 1;
+            attractor.max_transient = attractor.max_transient.max(transient);
+        }
+        Basins {
+            attractors, 
+            basin, 
+            transient, 
+        }
+    }
+
+    /// Overwrites `cells` with the configuration with the given state number. 
+    fn fill(&self, state: u32, cells: &mut Cells) {
+        cells.0.clear();
+        cells.0.extend((0..self.width).rev().map(|bit| (state >> bit & 1) as u8));
+    }
+}
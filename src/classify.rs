@@ -0,0 +1,239 @@
+use std::fmt::{self, Display, Formatter};
+use rand::Rng;
+use crate::{block_entropy, Automaton, Cells, CycleDetector, Damage, EdgeHandling, Rule, Settings};
+
+/// Number of cells in the ring each sample is run on. 
+const WIDTH: u16 = 201;
+/// Number of generations each sample is run for while looking for a cycle. 
+const GENERATIONS: u64 = 512;
+/// Generation in which the entropy is first measured, once the random initial configuration has been
+/// smoothed out. 
+const EARLY: u64 = 16;
+/// Size of the blocks the entropy is measured for. 
+const BLOCK_SIZE: usize = 8;
+/// Number of generations the damage is left to spread for, about the time it takes to reach the edges. 
+const DAMAGE_GENERATIONS: u64 = 96;
+/// Chaos index from which the behaviour counts as chaotic. 
+const CHAOTIC: f64 = 0.45;
+/// Damage speed below which the damage counts as not spreading at all. 
+const SLOW: f64 = 0.1;
+/// Decline of the block entropy per cell from which localised structures count as emerging. 
+const EMERGING: f64 = 0.05;
+/// Largest median deviation of the damage speeds from their median for the spreading to count as regular. 
+const REGULAR: f64 = 0.15;
+
+/// Wolfram's four classes of cellular automaton behaviour. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Class {
+    /// Class I: evolves to a uniform configuration. 
+    Uniform, 
+    /// Class II: evolves to stable or periodic, localised structures. 
+    Periodic, 
+    /// Class III: evolves chaotically. 
+    Chaotic, 
+    /// Class IV: evolves complex, long-lived localised structures. 
+    Complex, 
+}
+
+impl Class {
+    /// The Roman numeral of the class. 
+    pub fn numeral(self) -> &'static str {
+        match self {
+            Class::Uniform => "I", 
+            Class::Periodic => "II", 
+            Class::Chaotic => "III", 
+            Class::Complex => "IV", 
+        }
+    }
+}
+
+impl Display for Class {
+    /// Formats the class as e.g. `III (chaotic)`. 
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Class::Uniform => "uniform", 
+            Class::Periodic => "periodic", 
+            Class::Chaotic => "chaotic", 
+            Class::Complex => "complex", 
+        };
+        write!(f, "{} ({name})", self.numeral())
+    }
+}
+
+/// A guess at the class of a rule, along with the measurements it's based on. 
+#[derive(Clone, Debug, PartialEq)]
+pub struct Classification {
+    pub class: Class, 
+    /// Fraction (0.0-1.0) of the samples that individually point to `class`. 
+    pub confidence: f64, 
+    /// Fraction of the samples that entered a cycle. 
+    pub cycled: f64, 
+    /// Median speed of the damage over the samples that neither entered a cycle nor healed, or 0 if there
+    /// are none. 
+    pub damage_speed: f64, 
+    /// Mean block entropy per cell (0.0-1.0), for blocks of 8 cells, over the samples that didn't enter a
+    /// cycle, or 0 if all did. 
+    pub entropy: f64, 
+    /// Mean decrease of the block entropy per cell since early on, which is large when localised structures
+    /// are left after the rest has settled. 
+    pub entropy_decline: f64, 
+}
+
+/// What a single sample says about a rule. 
+struct Sample {
+    /// Whether the sample entered a cycle, and if so, whether its configuration is uniform. 
+    cycle: Option<bool>, 
+    /// Mean speed of the edges of the damage, unless the sample entered a cycle or the damage healed. 
+    speed: Option<f64>, 
+    entropy: f64, 
+    entropy_decline: f64, 
+}
+
+impl Sample {
+    /// The class the sample points to on its own. 
+    fn class(&self) -> Class {
+        match (self.cycle, self.speed) {
+            (Some(true), _) => Class::Uniform, 
+            (Some(false), _) | (None, None) => Class::Periodic, 
+            (None, Some(speed)) if speed < SLOW => Class::Periodic, 
+            (None, Some(speed)) if chaos(speed, self.entropy_decline) < CHAOTIC => Class::Complex, 
+            (None, Some(_)) => Class::Chaotic, 
+        }
+    }
+}
+
+/// How chaotic the behaviour is: damage spreading fast suggests chaos, unless the entropy is declining, which
+/// suggests structures emerging from the chaos. 
+fn chaos(damage_speed: f64, entropy_decline: f64) -> f64 {
+    damage_speed - 2.0 * entropy_decline
+}
+
+/// Guesses the Wolfram class of a rule from `samples` random configurations on a ring. 
+///
+/// Each sample is run until it enters a cycle, which points to class I if the cycle is a uniform fixed
+/// point and class II otherwise. If most samples don't, the rule is judged by how fast and how regularly
+/// a single flipped cell spreads once the transients have died down, and by whether the block entropy
+/// declines as it would when localised structures are left behind: damage that spreads in fewer than half
+/// the samples while no such structures emerge, or hardly any entropy, points to class II, damage spreading
+/// fast and regularly without a declining entropy to class III, and anything in between to class IV. 
+///
+/// Equivalent rules (see [`Rule::equivalents`]) behave the same up to mirroring and complementing the cells, 
+/// so the samples are always run on the [canonical](Rule::canonical) rule, and all four get the same
+/// classification from the same random number generator. 
+///
+/// This is a heuristic, and class IV in particular is notoriously hard to tell apart. 
+///
+/// ```
+/// use eca_explorer::{classify, Class, Rule};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(0);
+/// assert_eq!(classify(Rule(0), 4, &mut rng).class, Class::Uniform);
+/// assert_eq!(classify(Rule(30), 4, &mut rng).class, Class::Chaotic);
+/// ```
+pub fn classify(rule: Rule, samples: usize, rng: &mut impl Rng) -> Classification {
+    assert!(samples > 0, "at least one sample is needed");
+    let rule = rule.canonical();
+    let samples: Vec<Sample> = (0..samples)
+        .map(|_| sample(rule, Cells::new_random(WIDTH, 0.5, rng)))
+        .collect();
+    let count = samples.len() as f64;
+    let cycled: Vec<&Sample> = samples.iter().filter(|sample| sample.cycle.is_some()).collect();
+    let uncycled: Vec<&Sample> = samples.iter().filter(|sample| sample.cycle.is_none()).collect();
+
+    // damage that heals says little about how fast it spreads where it doesn't
+    // and the median keeps a single sample whose damage got stuck from hiding the spreading of the rest
+    let speeds: Vec<f64> = uncycled.iter().filter_map(|sample| sample.speed).collect();
+    let damage_speed = median(speeds.clone());
+    let deviation = median(speeds.iter().map(|speed| (speed - damage_speed).abs()).collect());
+    let entropy = mean(uncycled.iter().map(|sample| sample.entropy));
+    let entropy_decline = mean(uncycled.iter().map(|sample| sample.entropy_decline));
+    // damage often gets stuck among the localised structures of class IV too, which can bring its median
+    // speed down to that of class II, but unlike in class II, the structures take a while to emerge
+    let spreading = speeds.iter().filter(|&&speed| speed >= SLOW).count();
+    let settled = 2 * spreading < uncycled.len() && entropy_decline < EMERGING;
+
+    let class = if cycled.len() > uncycled.len() {
+        let uniform = cycled.iter().filter(|sample| sample.cycle == Some(true)).count();
+        match 2 * uniform > cycled.len() {
+            true => Class::Uniform, 
+            false => Class::Periodic, 
+        }
+    } else if settled || entropy < SLOW {
+        Class::Periodic
+    } else if chaos(damage_speed, entropy_decline) >= CHAOTIC && deviation <= REGULAR {
+        Class::Chaotic
+    } else {
+        Class::Complex
+    };
+    let agreeing = samples.iter().filter(|sample| sample.class() == class).count();
+    Classification {
+        class, 
+        confidence: agreeing as f64 / count, 
+        cycled: cycled.len() as f64 / count, 
+        damage_speed, 
+        entropy, 
+        entropy_decline, 
+    }
+}
+
+/// Runs a single sample, looking for a cycle and otherwise measuring the damage spreading. 
+fn sample(rule: Rule, initial: Cells) -> Sample {
+    let settings = Settings::new(rule, EdgeHandling::Wrap);
+    let mut automaton = Automaton::new(initial, settings.clone());
+    let mut detector = CycleDetector::new(EdgeHandling::Wrap);
+    let mut early_entropy = 0.0;
+    while automaton.generation() < GENERATIONS {
+        if automaton.generation() == EARLY {
+            early_entropy = block_entropy(automaton.current(), BLOCK_SIZE) / BLOCK_SIZE as f64;
+        }
+        if detector.push(automaton.generation(), automaton.current()).is_some() {
+            let cells = &automaton.current().0;
+            let uniform = cells.iter().all(|&cell| cell == cells[0]);
+            return Sample {
+                cycle: Some(uniform), 
+                speed: None, 
+                entropy: 0.0, 
+                entropy_decline: 0.0, 
+            }
+        }
+        automaton.advance();
+    }
+
+    let current = automaton.current().clone();
+    let entropy = block_entropy(&current, BLOCK_SIZE) / BLOCK_SIZE as f64;
+    let mut damage = Damage::new(current, settings, WIDTH as usize / 2);
+    for _ in 0..DAMAGE_GENERATIONS {
+        damage.advance();
+    }
+    let spreading = damage.spreading();
+    let speed = match spreading.healed {
+        Some(_) => None, 
+        None => Some((spreading.left_speed + spreading.right_speed) / 2.0), 
+    };
+    Sample {
+        cycle: None, 
+        speed, 
+        entropy, 
+        entropy_decline: early_entropy - entropy, 
+    }
+}
+
+/// The median of the values, or 0 if there are none. 
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(f64::total_cmp);
+    match values.len() {
+        0 => 0.0, 
+        len if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2.0, 
+        len => values[len / 2], 
+    }
+}
+
+/// The mean of the values, or 0 if there are none. 
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    match count {
+        0 => 0.0, 
+        count => sum / count as f64, 
+    }
+}
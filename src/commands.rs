@@ -0,0 +1,52 @@
+use clap::Args;
+use eca_explorer::{Cells, Pattern};
+use rand::Rng;
+
+mod atlas;
+mod basins;
+mod classify;
+mod damage;
+mod info;
+mod preimages;
+mod reverse;
+
+pub use atlas::Atlas;
+pub use basins::Basins;
+pub use classify::Classify;
+pub use damage::Damage;
+pub use info::Info;
+pub use preimages::Preimages;
+pub use reverse::Reverse;
+
+/// Width used for patterns without a width of their own, if none is given. 
+const DEFAULT_WIDTH: u16 = 64;
+
+/// A cell configuration given as a pattern, along with a width for patterns without a width of their own. 
+#[derive(Args)]
+struct Configuration {
+    /// Cell configuration, in the same syntax as the initial configuration when running a single rule. If
+    /// not specified, a random configuration is used. 
+    cells: Option<Pattern>, 
+
+    /// Width of the configuration, for patterns without a width of their own. Defaults to 64 cells. 
+    #[arg(long, short, value_parser=clap::value_parser!(u16).range(3..))]
+    width: Option<u16>, 
+}
+
+impl Configuration {
+    /// Builds the configuration, drawing random cells from `rng`. 
+    fn build(&self, rng: &mut impl Rng) -> Result<Cells, &'static str> {
+        let random = Pattern::Random(0.5);
+        let pattern = self.cells.as_ref().unwrap_or(&random);
+        let width = match (self.width, pattern.natural_width()) {
+            (None, Some(_)) => None, 
+            (width, _) => Some(width.unwrap_or(DEFAULT_WIDTH)), 
+        };
+        pattern.build(width, rng)
+    }
+
+    /// Whether the cells are drawn at random, in which case the seed is worth reporting. 
+    fn is_random(&self) -> bool {
+        matches!(self.cells, None | Some(Pattern::Random(_)))
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    fmt::Write as _, 
+    fs::{self, File}, 
+    io::{self, BufWriter}, 
+    path::{Path, PathBuf}, 
+};
+use clap::{Args, ValueEnum};
+use eca_explorer::{Automaton, Cells, EdgeHandling, Rule, Settings};
+use main_error::MainResult;
+use rand::{rngs::StdRng, SeedableRng};
+use crate::{commands::Configuration, output::{svg, Colour}};
+
+/// Glyphs of the digits 0-9, 3 pixels wide and 5 pixels tall. Each row is a 3-bit mask, most significant
+/// bit on the left. 
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111], 
+    [0b010, 0b110, 0b010, 0b010, 0b111], 
+    [0b111, 0b001, 0b111, 0b100, 0b111], 
+    [0b111, 0b001, 0b111, 0b001, 0b111], 
+    [0b101, 0b101, 0b111, 0b001, 0b001], 
+    [0b111, 0b100, 0b111, 0b001, 0b111], 
+    [0b111, 0b100, 0b111, 0b101, 0b111], 
+    [0b111, 0b001, 0b001, 0b001, 0b001], 
+    [0b111, 0b101, 0b111, 0b101, 0b111], 
+    [0b111, 0b101, 0b111, 0b001, 0b111], 
+];
+/// Scale of the digit glyphs in PNG labels. 
+const DIGIT_SCALE: usize = 2;
+/// Space in pixels between tiles in PNG atlases. 
+const GAP: usize = 8;
+/// Height in pixels of the label above each tile in PNG atlases. 
+const LABEL_HEIGHT: usize = 5 * DIGIT_SCALE + 4;
+/// Width in pixels of the widest label, a 3-digit rule number with a pixel between digits. Tiles in PNG
+/// atlases are at least this wide, so that labels don't spill into the next tile. 
+const LABEL_WIDTH: usize = (3 * 4 - 1) * DIGIT_SCALE;
+
+/// Run every rule from the same initial configuration, and tile the diagrams into a labelled atlas. 
+#[derive(Args)]
+pub struct Atlas {
+    /// File to write the atlas to. 
+    output: PathBuf, 
+
+    /// Initial configuration shared by all rules. 
+    #[command(flatten)]
+    initial: Configuration, 
+
+    /// Only include the 88 canonical rules, i.e. one rule per equivalence class. 
+    #[arg(long)]
+    canonical: bool, 
+
+    /// Format of the atlas. If not specified, it's guessed from the file extension. 
+    #[arg(long, short)]
+    format: Option<Format>, 
+
+    /// Number of generations to run each rule for. 
+    #[arg(long, short, default_value_t=32)]
+    generations: u16, 
+
+    /// How the two edges are handled. 
+    #[arg(long, short, default_value="wrap")]
+    edges: EdgeHandling, 
+
+    /// Number of tiles in each row of the atlas. 
+    #[arg(long, short, default_value_t=16, value_parser=clap::value_parser!(u16).range(1..))]
+    columns: u16, 
+
+    /// Width and height in pixels of each cell. 
+    #[arg(long, default_value_t=2, value_parser=clap::value_parser!(u16).range(1..))]
+    cell_size: u16, 
+
+    /// Colour of live cells. 
+    #[arg(long, default_value="#000000")]
+    alive_colour: Colour, 
+
+    /// Colour of dead cells. 
+    #[arg(long, default_value="#ffffff")]
+    dead_colour: Colour, 
+
+    /// Seed for the random number generator used for a random initial configuration. 
+    #[arg(long)]
+    seed: Option<u64>, 
+}
+
+/// File format of the atlas. 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+enum Format {
+    /// A single PNG image with the rule number above each tile. 
+    Png, 
+    /// An HTML page with each tile as an inline SVG image in a grid. 
+    Html, 
+}
+
+/// The spacetime diagram of a single rule. 
+struct Tile {
+    rule: Rule, 
+    rows: Vec<Vec<u8>>, 
+}
+
+impl Atlas {
+    pub fn run(self) -> MainResult {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let initial = self.initial.build(&mut StdRng::seed_from_u64(seed))?;
+        if self.initial.is_random() {
+            eprintln!("Seed: {seed}");
+        }
+
+        let tiles: Vec<Tile> = Rule::all()
+            .filter(|rule| !self.canonical || rule.is_canonical())
+            .map(|rule| self.tile(rule, &initial))
+            .collect();
+        let format = self.format.unwrap_or(match self.output.extension() {
+            Some(extension) if extension == "html" || extension == "htm" => Format::Html, 
+            _ => Format::Png, 
+        });
+        match format {
+            Format::Png => self.write_png(&tiles, initial.0.len(), &self.output)?, 
+            Format::Html => fs::write(&self.output, self.html(&tiles))?, 
+        }
+        Ok(())
+    }
+
+    fn tile(&self, rule: Rule, initial: &Cells) -> Tile {
+        let settings = Settings::new(rule, self.edges);
+        let rows = Automaton::new(initial.clone(), settings)
+            .take(self.generations.into())
+            .map(|cells| cells.0)
+            .collect();
+        Tile { rule, rows }
+    }
+
+    fn html(&self, tiles: &[Tile]) -> String {
+        let style = svg::Style {
+            cell_size: self.cell_size, 
+            colours: [self.dead_colour, self.alive_colour], 
+            grid: false, 
+            caption: None, 
+        };
+        // writing to a string never fails
+        let mut html = String::new();
+        html += "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Rule atlas</title>\n";
+        let _ = writeln!(
+            html, 
+            concat!(
+                "<style>\n", 
+                "body {{ font-family: sans-serif; }}\n", 
+                ".atlas {{ display: grid; grid-template-columns: repeat({}, max-content); gap: 12px; }}\n", 
+                "figure {{ margin: 0; }}\n", 
+                "figcaption {{ text-align: center; }}\n", 
+                "</style>", 
+            ), 
+            self.columns, 
+        );
+        html += "</head>\n<body>\n<div class=\"atlas\">\n";
+        for Tile { rule, rows } in tiles {
+            let _ = writeln!(
+                html, 
+                "<figure>\n{}<figcaption>Rule {}</figcaption>\n</figure>", 
+                svg::document(rows, &style), 
+                rule.0, 
+            );
+        }
+        html += "</div>\n</body>\n</html>\n";
+        html
+    }
+
+    /// Draws the tiles of diagrams `cells` wide into an image, returning its width and height in pixels and
+    /// its pixels row by row. 
+    fn png(&self, tiles: &[Tile], cells: usize) -> (usize, usize, Vec<[u8; 3]>) {
+        let cell_size = usize::from(self.cell_size);
+        let tile_width = (cells * cell_size).max(LABEL_WIDTH);
+        let tile_height = LABEL_HEIGHT + usize::from(self.generations) * cell_size;
+        let columns = usize::from(self.columns).min(tiles.len());
+        let rows = tiles.len().div_ceil(columns);
+        let width = GAP + columns * (tile_width + GAP);
+        let height = GAP + rows * (tile_height + GAP);
+
+        let [Colour(dead), Colour(alive)] = [self.dead_colour, self.alive_colour];
+        let mut pixels = vec![dead; width * height];
+        let mut fill = |x: usize, y: usize, size: usize| {
+            for y in y..y + size {
+                pixels[y * width + x..][..size].fill(alive);
+            }
+        };
+
+        for (i, Tile { rule, rows }) in tiles.iter().enumerate() {
+            let left = GAP + i % columns * (tile_width + GAP);
+            let top = GAP + i / columns * (tile_height + GAP);
+
+            // label, drawn in digit glyphs
+            for (d, digit) in rule.0.to_string().bytes().enumerate() {
+                let glyph = DIGITS[usize::from(digit - b'0')];
+                for (y, row) in glyph.iter().enumerate() {
+                    for x in (0..3).filter(|x| row >> (2 - x) & 1 != 0) {
+                        let x = left + (d * 4 + x) * DIGIT_SCALE;
+                        fill(x, top + y * DIGIT_SCALE, DIGIT_SCALE);
+                    }
+                }
+            }
+
+            // diagram
+            let top = top + LABEL_HEIGHT;
+            for (y, row) in rows.iter().enumerate() {
+                for x in row.iter().enumerate().filter(|(_, &cell)| cell != 0).map(|(x, _)| x) {
+                    fill(left + x * cell_size, top + y * cell_size, cell_size);
+                }
+            }
+        }
+        (width, height, pixels)
+    }
+
+    fn write_png(&self, tiles: &[Tile], cells: usize, path: &Path) -> io::Result<()> {
+        let (width, height, pixels) = self.png(tiles, cells);
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(pixels.as_flattened()))
+            .map_err(io::Error::other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use super::*;
+
+    #[derive(Parser)]
+    struct Command {
+        #[command(flatten)]
+        atlas: Atlas, 
+    }
+
+    /// The atlas and its tiles for the given rules, from the arguments of the `atlas` command. 
+    fn tiles(args: &[&str], rules: &[u8]) -> (Atlas, Vec<Tile>) {
+        let atlas = Command::parse_from(["atlas"].iter().chain(args)).atlas;
+        let initial = atlas.initial.build(&mut StdRng::seed_from_u64(0)).unwrap();
+        let tiles = rules.iter().map(|&code| atlas.tile(Rule(code), &initial)).collect();
+        (atlas, tiles)
+    }
+
+    #[test]
+    fn png_lays_tiles_out_in_rows_below_their_labels() {
+        let args = ["atlas.png", "010", "-g", "2", "-c", "2", "--cell-size", "1"];
+        let (atlas, tiles) = tiles(&args, &[0, 255, 204]);
+        let (width, height, pixels) = atlas.png(&tiles, 3);
+        // tiles as wide as the widest label, and as tall as the label and 2 generations
+        let (tile_width, tile_height) = (LABEL_WIDTH, LABEL_HEIGHT + 2);
+        assert_eq!((width, height), (GAP + 2 * (tile_width + GAP), GAP + 2 * (tile_height + GAP)));
+        let alive = |x: usize, y: usize| pixels[y * width + x] == [0, 0, 0];
+
+        // the top row of the 0 labelling the first tile
+        assert!((GAP..GAP + 3 * DIGIT_SCALE).all(|x| alive(x, GAP)));
+        // rule 255 fills the second generation of the second tile
+        let (left, top) = (GAP + tile_width + GAP, GAP + LABEL_HEIGHT);
+        assert_eq!((0..4).map(|x| alive(left + x, top)).collect::<Vec<_>>(), [false, true, false, false]);
+        assert_eq!((0..4).map(|x| alive(left + x, top + 1)).collect::<Vec<_>>(), [true, true, true, false]);
+        // rule 204 keeps the cell of the third tile, which starts the second row of tiles
+        let (left, top) = (GAP, GAP + tile_height + GAP + LABEL_HEIGHT);
+        assert!(alive(left + 1, top) && alive(left + 1, top + 1));
+        assert!(!alive(left, top) && !alive(left + 2, top + 1));
+    }
+
+    #[test]
+    fn html_has_a_captioned_figure_per_rule() {
+        let (atlas, tiles) = tiles(&["atlas.html", "010", "-g", "3", "-c", "5"], &[30, 110]);
+        let html = atlas.html(&tiles);
+        assert!(html.starts_with("<!DOCTYPE html>\n") && html.ends_with("</html>\n"));
+        assert!(html.contains("grid-template-columns: repeat(5, max-content)"));
+        assert_eq!(html.matches("<figure>\n<svg").count(), 2);
+        assert!(html.contains("<figcaption>Rule 30</figcaption>"));
+        assert!(html.contains("<figcaption>Rule 110</figcaption>"));
+    }
+}
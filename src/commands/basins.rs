@@ -0,0 +1,191 @@
+use std::{
+    fmt::Write as _, 
+    fs, 
+    path::PathBuf, 
+};
+use clap::{Args, ValueEnum};
+use eca_explorer::{EdgeHandling, Rule, Settings, StateGraph};
+use main_error::MainResult;
+
+/// Enumerate all configurations of a few cells, and analyse the basins of attraction of a rule. 
+#[derive(Args)]
+pub struct Basins {
+    /// The Wolfram code (0-255) of the rule. 
+    rule: u8, 
+
+    /// Number of cells (3-24). The state-transition graph has 2^width states. 
+    #[arg(long, short, default_value_t=8, value_parser=clap::value_parser!(u16).range(3..=24))]
+    width: u16, 
+
+    /// How the two edges are handled. 
+    #[arg(long, short, default_value="wrap")]
+    edges: EdgeHandling, 
+
+    /// Maximum number of attractors and Garden-of-Eden states listed. All of them are still counted. 
+    #[arg(long, default_value_t=20)]
+    limit: usize, 
+
+    /// Write the state-transition graph to a Graphviz DOT file, with one cluster per basin. 
+    #[arg(long)]
+    dot: Option<PathBuf>, 
+
+    /// Write the state-transition graph and basins to a JSON file. 
+    #[arg(long)]
+    json: Option<PathBuf>, 
+}
+
+impl Basins {
+    pub fn run(self) -> MainResult {
+        let rule = Rule(self.rule);
+        let graph = StateGraph::new(self.width, &Settings::new(rule, self.edges))?;
+        let basins = graph.basins();
+        let gardens: Vec<u32> = graph.gardens_of_eden().collect();
+        let bits = |state: u32| format!("{state:0width$b}", width = graph.width() as usize);
+
+        println!("Rule {} on {} cells ({} edges)", rule.0, graph.width(), self.edges_name());
+        println!();
+        println!("  states              {}", graph.len());
+        println!("  attractors          {}", basins.attractors.len());
+        println!("  gardens of Eden     {}", gardens.len());
+        println!("  max transient       {}", basins.transient.iter().max().unwrap());
+        println!();
+        println!("Attractors by basin size");
+        println!("  {:>8}  {:>10}  {:>13}  first state of cycle", "period", "basin size", "max transient");
+        let mut attractors: Vec<_> = basins.attractors.iter().collect();
+        attractors.sort_by_key(|attractor| std::cmp::Reverse(attractor.size));
+        for attractor in attractors.iter().take(self.limit) {
+            println!(
+                "  {:>8}  {:>10}  {:>13}  {}", 
+                attractor.cycle.len(), 
+                attractor.size, 
+                attractor.max_transient, 
+                bits(attractor.cycle[0]), 
+            );
+        }
+        if attractors.len() > self.limit {
+            println!("  ... and {} more", attractors.len() - self.limit);
+        }
+        println!();
+        println!("Gardens of Eden");
+        for &state in gardens.iter().take(self.limit) {
+            println!("  {}", bits(state));
+        }
+        match gardens.len() {
+            0 => println!("  none"), 
+            count if count > self.limit => println!("  ... and {} more", count - self.limit), 
+            _ => (), 
+        }
+
+        if let Some(path) = &self.dot {
+            fs::write(path, self.dot(&graph, &basins))?;
+        }
+        if let Some(path) = &self.json {
+            fs::write(path, self.json(&graph, &basins))?;
+        }
+        Ok(())
+    }
+
+    fn edges_name(&self) -> String {
+        self.edges.to_possible_value().unwrap().get_name().to_owned()
+    }
+
+    /// The graph in Graphviz DOT, with each basin in its own cluster. Cycle states are drawn as circles, 
+    /// Gardens of Eden as small circles and all other states as points; hovering over a state shows its cells. 
+    fn dot(&self, graph: &StateGraph, basins: &eca_explorer::Basins) -> String {
+        let in_degrees = graph.in_degrees();
+        // writing to a string never fails
+        let mut dot = String::new();
+        dot += "digraph basins {\n";
+        let _ = writeln!(
+            dot, 
+            "    label=\"Rule {} on {} cells ({} edges)\";", 
+            self.rule, 
+            graph.width(), 
+            self.edges_name(), 
+        );
+        dot += "    node [shape=point, width=0.05, label=\"\"];\n";
+        dot += "    edge [arrowsize=0.3];\n";
+
+        // group the states by basin
+        let mut members = vec![Vec::new(); basins.attractors.len()];
+        for (state, &basin) in basins.basin.iter().enumerate() {
+            members[basin as usize].push(state as u32);
+        }
+        for (index, (attractor, states)) in basins.attractors.iter().zip(&members).enumerate() {
+            let _ = writeln!(dot, "    subgraph cluster_{index} {{");
+            let _ = writeln!(
+                dot, 
+                "        label=\"period {}, basin size {}\";", 
+                attractor.cycle.len(), 
+                attractor.size, 
+            );
+            for &state in states {
+                let shape = match (basins.transient[state as usize], in_degrees[state as usize]) {
+                    (0, _) => ", shape=circle, width=0.1", 
+                    (_, 0) => ", shape=circle, width=0.05", 
+                    _ => "", 
+                };
+                let _ = writeln!(
+                    dot, 
+                    "        {state} [tooltip=\"{:0width$b}\"{shape}];", 
+                    state, 
+                    width = graph.width() as usize, 
+                );
+            }
+            dot += "    }\n";
+        }
+        for state in 0..graph.len() as u32 {
+            let _ = writeln!(dot, "    {state} -> {};", graph.successor(state));
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// The graph and its basins in JSON. States are given by their cells as strings of ones and zeroes. 
+    fn json(&self, graph: &StateGraph, basins: &eca_explorer::Basins) -> String {
+        let in_degrees = graph.in_degrees();
+        let bits = |state: u32| format!("\"{state:0width$b}\"", width = graph.width() as usize);
+        // writing to a string never fails
+        let mut json = String::new();
+        json += "{\n";
+        let _ = writeln!(json, "  \"rule\": {},", self.rule);
+        let _ = writeln!(json, "  \"width\": {},", graph.width());
+        let _ = writeln!(json, "  \"edges\": \"{}\",", self.edges_name());
+
+        json += "  \"attractors\": [\n";
+        for (index, attractor) in basins.attractors.iter().enumerate() {
+            let cycle: Vec<String> = attractor.cycle
+                .iter()
+                .map(|&state| bits(state))
+                .collect();
+            let separator = if index + 1 < basins.attractors.len() { "," } else { "" };
+            let _ = writeln!(
+                json, 
+                "    {{\"period\": {}, \"basin_size\": {}, \"max_transient\": {}, \"cycle\": [{}]}}{separator}", 
+                attractor.cycle.len(), 
+                attractor.size, 
+                attractor.max_transient, 
+                cycle.join(", "), 
+            );
+        }
+        json += "  ],\n";
+
+        json += "  \"states\": [\n";
+        for state in 0..graph.len() as u32 {
+            let separator = if (state as usize) + 1 < graph.len() { "," } else { "" };
+            let _ = writeln!(
+                json, 
+                "    {{\"cells\": {}, \"successor\": {}, \"basin\": {}, \"transient\": {}, \
+                \"garden_of_eden\": {}}}{separator}", 
+                bits(state), 
+                bits(graph.successor(state)), 
+                basins.basin[state as usize], 
+                basins.transient[state as usize], 
+                in_degrees[state as usize] == 0, 
+            );
+        }
+        json += "  ]\n";
+        json += "}\n";
+        json
+    }
+}
@@ -0,0 +1,71 @@
+use clap::Args;
+use eca_explorer::{classify, Classification, Rule};
+use main_error::MainResult;
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Guess the Wolfram class (I uniform, II periodic, III chaotic or IV complex) of a rule from a number of
+/// random samples. 
+#[derive(Args)]
+pub struct Classify {
+    /// The Wolfram code (0-255) of the rule. 
+    #[arg(required_unless_present="all")]
+    rule: Option<u8>, 
+
+    /// Classify all 256 rules, and print a table. 
+    #[arg(long, conflicts_with="rule")]
+    all: bool, 
+
+    /// Number of random configurations sampled for each rule. 
+    #[arg(long, default_value_t=8, value_parser=clap::value_parser!(u16).range(1..))]
+    samples: u16, 
+
+    /// Seed for the random number generator used for the samples. If not specified, a random seed is used, 
+    /// which is printed on exit. 
+    #[arg(long)]
+    seed: Option<u64>, 
+}
+
+impl Classify {
+    pub fn run(self) -> MainResult {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let samples = self.samples.into();
+        match self.rule {
+            Some(code) => {
+                let classification = classify(Rule(code), samples, &mut rng);
+                let Classification { class, confidence, cycled, damage_speed, entropy, entropy_decline } =
+                    classification;
+                println!("Rule {code}");
+                println!();
+                println!("  class               {class}");
+                println!("  confidence          {confidence:.2}");
+                println!();
+                println!("Measurements");
+                println!("  cycled              {cycled:.2}");
+                println!("  damage speed        {damage_speed:.2}");
+                println!("  entropy             {entropy:.2}");
+                println!("  entropy decline     {entropy_decline:.2}");
+            }
+            None => {
+                println!("rule  class  confidence  cycled  damage speed  entropy  entropy decline");
+                for rule in Rule::all() {
+                    let classification = classify(rule, samples, &mut rng);
+                    println!(
+                        "{:>4}  {:>5}  {:>10.2}  {:>6.2}  {:>12.2}  {:>7.2}  {:>15.2}", 
+                        rule.0, 
+                        classification.class.numeral(), 
+                        classification.confidence, 
+                        classification.cycled, 
+                        classification.damage_speed, 
+                        classification.entropy, 
+                        classification.entropy_decline, 
+                    );
+                }
+            }
+        }
+        if self.seed.is_none() {
+            eprintln!("Seed: {seed}");
+        }
+        Ok(())
+    }
+}
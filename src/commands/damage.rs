@@ -0,0 +1,82 @@
+use std::io::{self, IsTerminal};
+use clap::Args;
+use crossterm::style::Stylize;
+use eca_explorer::{Damage as Copies, EdgeHandling, Rule, Settings};
+use main_error::MainResult;
+use rand::{rngs::StdRng, SeedableRng};
+use crate::commands::Configuration;
+
+/// Run two copies of a rule from configurations differing in a single cell, and measure how the damage
+/// spreads. The configuration given is that of the unperturbed copy. 
+#[derive(Args)]
+pub struct Damage {
+    /// The Wolfram code (0-255) of the rule. 
+    rule: u8, 
+
+    #[command(flatten)]
+    initial: Configuration, 
+
+    /// Number of generations to run for. 
+    #[arg(long, short, default_value_t=32)]
+    generations: u16, 
+
+    /// How the two edges are handled. 
+    #[arg(long, short, default_value="wrap")]
+    edges: EdgeHandling, 
+
+    /// Index of the cell flipped in the perturbed copy. If not specified, the centre cell is flipped. 
+    #[arg(long)]
+    flip: Option<usize>, 
+
+    /// Seed for the random number generator used for a random initial configuration. 
+    #[arg(long)]
+    seed: Option<u64>, 
+}
+
+impl Damage {
+    pub fn run(self) -> MainResult {
+        let rule = Rule(self.rule);
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let initial = self.initial.build(&mut StdRng::seed_from_u64(seed))?;
+        let flip = self.flip.unwrap_or(initial.0.len() / 2);
+        if flip >= initial.0.len() {
+            return Err("The flipped cell must be within the configuration".into())
+        }
+
+        // damaged cells are highlighted on top of the unperturbed copy, or shown as `x` without a terminal
+        let terminal = io::stdout().is_terminal();
+        let mut damage = Copies::new(initial, Settings::new(rule, self.edges), flip);
+        for generation in 0..self.generations {
+            if generation > 0 {
+                damage.advance();
+            }
+            let difference = damage.difference();
+            let line: String = damage.original().0
+                .iter()
+                .zip(&difference.0)
+                .map(|(&cell, &damaged)| match (terminal, damaged != 0) {
+                    (true, true) => "██".red().to_string(), 
+                    (true, false) => if cell != 0 { "██" } else { "╶╴" }.dim().to_string(), 
+                    (false, true) => "x".to_owned(), 
+                    (false, false) => cell.to_string(), 
+                })
+                .collect();
+            println!("{line}");
+        }
+
+        let spreading = damage.spreading();
+        println!();
+        println!("Damage spreading of rule {} from cell {flip}", rule.0);
+        println!("  left speed          {:.3} cells/generation", spreading.left_speed);
+        println!("  right speed         {:.3} cells/generation", spreading.right_speed);
+        println!("  area growth rate    {:.3} cells/generation", spreading.growth_rate);
+        match spreading.healed {
+            Some(generation) => println!("  healed in generation {generation}"), 
+            None => println!("  measured over       {} generations", spreading.generations), 
+        }
+        if self.initial.is_random() {
+            eprintln!("Seed: {seed}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use clap::Args;
+use eca_explorer::Rule;
+
+/// Print the equivalent rules, lookup table and properties of a rule. 
+#[derive(Args)]
+pub struct Info {
+    /// The Wolfram code (0-255) of the rule. 
+    rule: u8, 
+}
+
+impl Info {
+    pub fn run(self) {
+        let rule = Rule(self.rule);
+        let yes_no = |property: bool| if property { "yes" } else { "no" };
+
+        println!("Rule {}", rule.0);
+        println!();
+        println!("Equivalent rules");
+        println!("  mirror              {}", rule.mirror().0);
+        println!("  complement          {}", rule.complement().0);
+        println!("  mirror complement   {}", rule.mirror_complement().0);
+        println!("  canonical           {}", rule.canonical().0);
+        println!();
+        println!("Lookup table");
+        let neighbourhoods: Vec<String> = (0..8).rev()
+            .map(|n| format!("{n:03b}"))
+            .collect();
+        let outputs: Vec<String> = (0..8).rev()
+            .map(|n| format!(" {} ", rule.0 >> n & 1))
+            .collect();
+        println!("  {}", neighbourhoods.join("  "));
+        println!("  {}", outputs.join("  ").trim_end());
+        println!();
+        println!("Properties");
+        println!("  symmetric           {}", yes_no(rule.is_symmetric()));
+        println!("  self-complementary  {}", yes_no(rule.is_self_complementary()));
+        println!("  additive            {}", yes_no(rule.is_additive()));
+        println!("  totalistic          {}", yes_no(rule.is_totalistic()));
+        println!("  number-conserving   {}", yes_no(rule.is_number_conserving()));
+    }
+}
@@ -0,0 +1,62 @@
+use clap::{Args, ValueEnum};
+use eca_explorer::{Cells, EdgeHandling, Preimages as Counter, Rule, Settings};
+use main_error::MainResult;
+use crate::commands::Configuration;
+
+/// Count and list the configurations a rule maps onto a given or random configuration. 
+#[derive(Args)]
+pub struct Preimages {
+    /// The Wolfram code (0-255) of the rule. 
+    rule: u8, 
+
+    #[command(flatten)]
+    cells: Configuration, 
+
+    /// How the two edges are handled. 
+    #[arg(long, short, default_value="wrap")]
+    edges: EdgeHandling, 
+
+    /// Maximum number of preimages listed. All of them are still counted. 
+    #[arg(long, default_value_t=20)]
+    limit: usize, 
+}
+
+impl Preimages {
+    pub fn run(self) -> MainResult {
+        let rule = Rule(self.rule);
+        let target = self.cells.build(&mut rand::thread_rng())?;
+        let preimages = Counter::new(&target, &Settings::new(rule, self.edges));
+        let count = preimages.count();
+        let edges = self.edges.to_possible_value().unwrap();
+
+        println!("Rule {} on {} cells ({} edges)", rule.0, target.0.len(), edges.get_name());
+        println!();
+        println!("  configuration       {}", bits(&target));
+        if count == u128::MAX {
+            println!("  preimages           at least {count}");
+        } else {
+            println!("  preimages           {count}");
+        }
+        println!();
+        if preimages.is_garden_of_eden() {
+            println!("The configuration is a Garden of Eden: no configuration leads to it.");
+            return Ok(())
+        }
+        println!("Preimages");
+        for cells in preimages.iter().take(self.limit) {
+            println!("  {}", bits(&cells));
+        }
+        if count > self.limit as u128 {
+            println!("  ... and {} more", count - self.limit as u128);
+        }
+        Ok(())
+    }
+}
+
+/// The cells as a string of ones and zeroes. 
+fn bits(cells: &Cells) -> String {
+    cells.0
+        .iter()
+        .map(|&cell| if cell != 0 { '1' } else { '0' })
+        .collect()
+}
@@ -0,0 +1,87 @@
+use std::io::{self, IsTerminal};
+use clap::Args;
+use crossterm::style::Stylize;
+use eca_explorer::{Automaton, Cells, EdgeHandling, Pattern, Rule, Settings};
+use main_error::MainResult;
+use rand::{rngs::StdRng, SeedableRng};
+use crate::commands::Configuration;
+
+/// Run a rule as a second-order rule (Fredkin's construction) for a number of generations, then backwards
+/// for as many, and check that the initial pair of generations is restored exactly. 
+#[derive(Args)]
+pub struct Reverse {
+    /// The Wolfram code (0-255) of the rule. 
+    rule: u8, 
+
+    #[command(flatten)]
+    initial: Configuration, 
+
+    /// Generation preceding the initial configuration, in the same syntax and with the same width. If not
+    /// specified, all cells are dead. 
+    #[arg(long)]
+    previous: Option<Pattern>, 
+
+    /// Number of generations to run for in each direction. 
+    #[arg(long, short, default_value_t=16)]
+    generations: u16, 
+
+    /// How the two edges are handled. 
+    #[arg(long, short, default_value="wrap")]
+    edges: EdgeHandling, 
+
+    /// Seed for the random number generator used for random configurations. 
+    #[arg(long)]
+    seed: Option<u64>, 
+}
+
+impl Reverse {
+    pub fn run(self) -> MainResult {
+        let seed = self.seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let initial = self.initial.build(&mut rng)?;
+        let previous = match &self.previous {
+            Some(previous) => {
+                let width = u16::try_from(initial.0.len()).map_err(|_| "Initial configuration is too wide")?;
+                previous.build(Some(width), &mut rng)?
+            }
+            None => Cells(vec![0; initial.0.len()]), 
+        };
+        if previous.0.len() != initial.0.len() {
+            return Err("The previous generation must be as wide as the initial configuration".into())
+        }
+
+        let mut settings = Settings::new(Rule(self.rule), self.edges);
+        settings.second_order = true;
+        let mut automaton = Automaton::with_previous(previous.clone(), initial.clone(), settings);
+        let terminal = io::stdout().is_terminal();
+        let print = |cells: &Cells| match terminal {
+            true => println!("{cells}"), 
+            false => println!("{}", cells.0.iter().map(u8::to_string).collect::<String>()), 
+        };
+
+        print(automaton.current());
+        for _ in 0..self.generations {
+            print(automaton.advance());
+        }
+        // going backwards retraces the generations, ending with the previous one
+        match terminal {
+            true => println!("{}", "╴reversed╶".dim()), 
+            false => println!(), 
+        }
+        automaton.reverse();
+        print(automaton.current());
+        for _ in 0..self.generations {
+            print(automaton.advance());
+        }
+
+        // the pair is swapped, like the direction of time
+        let restored = automaton.current() == &previous && automaton.previous() == &initial;
+        println!();
+        println!("Second-order rule {} over {} generations each way", self.rule, self.generations);
+        println!("  pair restored       {}", if restored { "yes" } else { "no" });
+        if self.initial.is_random() {
+            eprintln!("Seed: {seed}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::HashMap, 
+    fmt::{self, Display, Formatter}, 
+    hash::{DefaultHasher, Hasher}, 
+};
+use crate::{Cells, EdgeHandling};
+
+/// The eventually periodic behaviour of a run. 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of generations before the cycle is entered. 
+    pub transient: u64, 
+    /// Number of generations after which the configuration repeats, up to the shift. 
+    pub period: u64, 
+    /// Number of cells the configuration has moved to the right (or left, if negative) after each period. 
+    /// Only non-zero with [`EdgeHandling::Wrap`]. 
+    pub shift: isize, 
+}
+
+impl Display for Cycle {
+    /// Formats the cycle as e.g. `transient 12, period 4, shift 1`, leaving out the shift if it is zero. 
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "transient {}, period {}", self.transient, self.period)?;
+        if self.shift != 0 {
+            write!(f, ", shift {}", self.shift)?;
+        }
+        Ok(())
+    }
+}
+
+/// Detects when a run enters a cycle by remembering every generation it has seen. 
+///
+/// Generations are remembered by a 64-bit digest rather than in full, so that memory doesn't grow with the
+/// width. Two configurations with the same digest would be taken for a cycle, but with 64 bits that is
+/// vanishingly unlikely. 
+///
+/// With [`EdgeHandling::Wrap`], the cells form a ring, so cycles that shift in space are also detected; the
+/// configurations are then compared up to rotation. 
+///
+/// ```
+/// use eca_explorer::{Automaton, Cells, Cycle, CycleDetector, EdgeHandling, Rule, Settings};
+///
+/// // rule 2 moves a single cell one step to the left each generation
+/// let settings = Settings::new(Rule(2), EdgeHandling::Wrap);
+/// let initial: Cells = "00100".parse().unwrap();
+/// let mut detector = CycleDetector::new(EdgeHandling::Wrap);
+/// let cycle = Automaton::new(initial, settings)
+///     .enumerate()
+///     .find_map(|(generation, cells)| detector.push(generation as u64, &cells));
+/// assert_eq!(cycle, Some(Cycle { transient: 0, period: 1, shift: -1 }));
+/// ```
+#[derive(Clone, Debug)]
+pub struct CycleDetector {
+    /// Whether configurations are compared up to rotation. 
+    rotations: bool, 
+    /// For the digest of each configuration seen (rotated to its canonical form), the generation it was
+    /// seen in and the rotation applied. 
+    seen: HashMap<u64, (u64, usize)>, 
+    cycle: Option<Cycle>, 
+}
+
+impl CycleDetector {
+    pub fn new(edge_handling: EdgeHandling) -> CycleDetector {
+        CycleDetector {
+            rotations: edge_handling == EdgeHandling::Wrap, 
+            seen: HashMap::new(), 
+            cycle: None, 
+        }
+    }
+
+    /// The cycle, if one has been found. 
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Forgets all generations seen, e.g. after the rule or configuration has changed. 
+    pub fn reset(&mut self) {
+        self.seen.clear();
+        self.cycle = None;
+    }
+
+    /// Records the configuration of the given generation, which must follow the previously pushed one. 
+    /// Returns the cycle once one has been found. 
+    pub fn push(&mut self, generation: u64, cells: &Cells) -> Option<Cycle> {
+        if self.cycle.is_some() {
+            return self.cycle
+        }
+        let width = cells.0.len();
+        let rotation = match self.rotations {
+            true => least_rotation(&cells.0), 
+            false => 0, 
+        };
+        // the digest of the rotated configuration, without building it
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&cells.0[rotation..]);
+        hasher.write(&cells.0[..rotation]);
+        let digest = hasher.finish();
+
+        match self.seen.get(&digest) {
+            Some(&(previous, previous_rotation)) => {
+                // rotating the previous generation right by `shift` cells yields the current one
+                let shift = (rotation + width - previous_rotation) % width;
+                let shift = match shift > width / 2 {
+                    true => shift as isize - width as isize, 
+                    false => shift as isize, 
+                };
+                self.cycle = Some(Cycle {
+                    transient: previous, 
+                    period: generation - previous, 
+                    shift, 
+                });
+                // the cycle won't change, so the generations seen are no longer needed
+                self.seen = HashMap::new();
+            }
+            None => {
+                self.seen.insert(digest, (generation, rotation));
+            }
+        }
+        self.cycle
+    }
+}
+
+/// The number of cells to rotate `cells` left by to get its lexicographically least rotation, in linear
+/// time. 
+fn least_rotation(cells: &[u8]) -> usize {
+    let n = cells.len();
+    // candidates `i` and `j` have matched for `k` cells
+    let (mut i, mut j, mut k) = (0, 1, 0);
+    while i < n && j < n && k < n {
+        let [a, b] = [cells[(i + k) % n], cells[(j + k) % n]];
+        if a == b {
+            k += 1;
+            continue
+        }
+        // the candidate with the higher cell can't be least, nor can any of the `k` cells following it
+        match a > b {
+            true => i += k + 1, 
+            false => j += k + 1, 
+        }
+        if i == j {
+            j += 1;
+        }
+        k = 0;
+    }
+    i.min(j)
+}
@@ -0,0 +1,164 @@
+use crate::{Automaton, Cells, Settings};
+
+/// Two automata run in lockstep from configurations differing in a single cell, to measure how the damage
+/// spreads. 
+///
+/// ```
+/// use eca_explorer::{Cells, Damage, EdgeHandling, Rule, Settings};
+///
+/// let initial: Cells = "0000000000000000000".parse().unwrap();
+/// let mut damage = Damage::new(initial, Settings::new(Rule(90), EdgeHandling::Wrap), 9);
+/// for _ in 0..5 {
+///     damage.advance();
+/// }
+/// // rule 90 is additive, so the damage evolves like a single live cell, spreading at the speed of light
+/// assert_eq!(damage.difference(), "0000101000001010000".parse().unwrap());
+/// let spreading = damage.spreading();
+/// assert_eq!([spreading.left_speed, spreading.right_speed], [1.0, 1.0]);
+/// assert_eq!(spreading.healed, None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Damage {
+    original: Automaton, 
+    perturbed: Automaton, 
+    /// Index of the flipped cell. 
+    origin: usize, 
+    /// Extent of the damage in each generation, while it hasn't healed or reached the edges. 
+    samples: Vec<Sample>, 
+    /// Set once the damage has healed or reached the edges, after which samples are no longer recorded. 
+    ended: bool, 
+    /// Generation in which the damage healed, if it did. 
+    healed: Option<u64>, 
+}
+
+/// Extent of the damage in a single generation. 
+#[derive(Clone, Copy, Debug)]
+struct Sample {
+    generation: u64, 
+    /// Distance from the origin to the leftmost damaged cell, negative if it's right of the origin. 
+    left: f64, 
+    /// Distance from the origin to the rightmost damaged cell, negative if it's left of the origin. 
+    right: f64, 
+    /// Number of damaged cells. 
+    area: f64, 
+}
+
+/// How fast the damage spread, measured until it healed or reached the edges. 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spreading {
+    /// Speed in cells per generation of the left edge of the damage. 
+    pub left_speed: f64, 
+    /// Speed in cells per generation of the right edge of the damage. 
+    pub right_speed: f64, 
+    /// Increase in damaged cells per generation. 
+    pub growth_rate: f64, 
+    /// Number of generations measured. 
+    pub generations: u64, 
+    /// Generation in which the two copies became identical again, if they did. 
+    pub healed: Option<u64>, 
+}
+
+impl Damage {
+    /// Starts the two copies, from `initial` and from `initial` with the cell at `flip` inverted, or advanced
+    /// to the next state for rules with more than two states. 
+    pub fn new(initial: Cells, settings: Settings, flip: usize) -> Damage {
+        assert!(flip < initial.0.len(), "flipped cell out of bounds");
+        let mut perturbed = initial.clone();
+        perturbed.0[flip] = (perturbed.0[flip] + 1) % settings.rule.states();
+        let mut damage = Damage {
+            original: Automaton::new(initial, settings.clone()), 
+            perturbed: Automaton::new(perturbed, settings), 
+            origin: flip, 
+            samples: Vec::new(), 
+            ended: false, 
+            healed: None, 
+        };
+        damage.sample();
+        damage
+    }
+
+    /// The current generation of the unperturbed copy. 
+    pub fn original(&self) -> &Cells {
+        self.original.current()
+    }
+
+    /// The current generation of the perturbed copy. 
+    pub fn perturbed(&self) -> &Cells {
+        self.perturbed.current()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.original.generation()
+    }
+
+    /// The cells differing between the two copies as live cells, i.e. the XOR of their current generations
+    /// for rules with two states. 
+    pub fn difference(&self) -> Cells {
+        let cells = self.original().0
+            .iter()
+            .zip(&self.perturbed().0)
+            .map(|(original, perturbed)| u8::from(original != perturbed))
+            .collect();
+        Cells(cells)
+    }
+
+    /// Computes the next generation of both copies. 
+    pub fn advance(&mut self) {
+        self.original.advance();
+        self.perturbed.advance();
+        self.sample();
+    }
+
+    /// Fits the spreading of the damage so far. The speeds and growth rate are the least-squares slopes of
+    /// the extent and area of the damage over the generations measured. 
+    pub fn spreading(&self) -> Spreading {
+        let slope = |value: fn(&Sample) -> f64| slope(self.samples.iter().map(|sample| {
+            (sample.generation as f64, value(sample))
+        }));
+        Spreading {
+            left_speed: slope(|sample| sample.left), 
+            right_speed: slope(|sample| sample.right), 
+            growth_rate: slope(|sample| sample.area), 
+            generations: self.samples.last().map_or(0, |sample| sample.generation), 
+            healed: self.healed, 
+        }
+    }
+
+    /// Records the extent of the damage in the current generation, unless measuring has ended. 
+    fn sample(&mut self) {
+        if self.ended {
+            return
+        }
+        let difference = self.difference();
+        let width = difference.0.len();
+        let damaged = || difference.0.iter().enumerate().filter(|&(_, &cell)| cell != 0).map(|(i, _)| i);
+        let (Some(leftmost), Some(rightmost)) = (damaged().next(), damaged().next_back()) else {
+            self.healed = Some(self.generation());
+            self.ended = true;
+            return
+        };
+        self.samples.push(Sample {
+            generation: self.generation(), 
+            left: self.origin as f64 - leftmost as f64, 
+            right: rightmost as f64 - self.origin as f64, 
+            area: damaged().count() as f64, 
+        });
+        // beyond the edges the damage can no longer spread freely
+        if leftmost == 0 || rightmost == width - 1 {
+            self.ended = true;
+        }
+    }
+}
+
+/// The least-squares slope of the points, or 0 if there are fewer than two. 
+fn slope(points: impl Iterator<Item = (f64, f64)> + Clone) -> f64 {
+    let count = points.clone().count() as f64;
+    if count < 2.0 {
+        return 0.0
+    }
+    let mean_x = points.clone().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.clone().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance: f64 = points.clone().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let variance: f64 = points.map(|(x, _)| (x - mean_x).powi(2)).sum();
+    covariance / variance
+}
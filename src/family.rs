@@ -0,0 +1,216 @@
+use std::fmt::{self, Display, Formatter};
+use clap::ValueEnum;
+use crate::Rule;
+
+/// How the code of a [`LocalRule`] is read. The code is a number in base `k`, the number of states, whose
+/// digit `n` (counting from the least significant) is the new state of a cell in the `n`th case. 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Family {
+    /// Digit `n` of the code is the new state of a cell whose neighbourhood, read as a base-`k` number with
+    /// the leftmost cell first, is `n`. This is the Wolfram code for two states and radius 1. 
+    Elementary, 
+    /// Digit `n` of the code is the new state of a cell whose neighbourhood, itself included, sums to `n`. 
+    Totalistic, 
+    /// Digit `k * n + c` of the code is the new state of a cell in state `c` whose surrounding cells, itself
+    /// excluded, sum to `n`. 
+    OuterTotalistic, 
+}
+
+/// Rule computing the new state of a cell from the `2 * radius + 1` cells around it, read according to its
+/// [`Family`]. Elementary rules with two states and radius 1 are equivalent to a [`Rule`]. 
+///
+/// ```
+/// use eca_explorer::{Family, LocalRule, Rule};
+///
+/// // rule 150 is the XOR of the whole neighbourhood, so it only depends on the number of live cells
+/// let parity = LocalRule::new(Family::Totalistic, 2, 1, 0b1010).unwrap();
+/// assert_eq!(parity.elementary(), Some(Rule(150)));
+/// // majority vote among 5 cells
+/// let majority = LocalRule::new(Family::Totalistic, 2, 2, 0b111000).unwrap();
+/// assert_eq!(majority.apply(&[1, 0, 1, 0, 1]), 1);
+/// assert_eq!(majority.apply(&[1, 0, 0, 0, 1]), 0);
+/// assert!(LocalRule::new(Family::Totalistic, 2, 1, 16).is_err());
+/// // Wolfram's 3-colour totalistic code 777 is 1001210 in base 3, so a neighbourhood summing to 2 gives 2
+/// let rule = LocalRule::new(Family::Totalistic, 3, 1, 777).unwrap();
+/// assert_eq!(rule.apply(&[0, 2, 0]), 2);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LocalRule {
+    family: Family, 
+    states: u8, 
+    radius: u8, 
+    code: u64, 
+}
+
+/// Largest number of states, so that each state can be written as a single digit. 
+pub const MAX_STATES: u8 = 10;
+
+impl LocalRule {
+    /// The rule of the given family, number of states and radius with code `code`. Fails if there are fewer
+    /// than 2 or more than [`MAX_STATES`] states, if the radius is 0, if there are so many possible codes
+    /// that they don't fit in a [`u64`], or if `code` isn't one of them. 
+    pub fn new(family: Family, states: u8, radius: u8, code: u64) -> Result<LocalRule, &'static str> {
+        if !(2..=MAX_STATES).contains(&states) {
+            return Err("Rules must have from 2 to 10 states")
+        }
+        if radius == 0 {
+            return Err("Rules must have a radius of at least 1")
+        }
+        let rule = LocalRule { family, states, radius, code: 0 };
+        if rule.codes().is_none() {
+            return Err("Too many states or too large a radius for the code of the rule to fit in 64 bits")
+        }
+        rule.with_code(code)
+    }
+
+    pub fn family(&self) -> Family {
+        self.family
+    }
+
+    /// Number of states a cell can be in. 
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    pub fn radius(&self) -> u8 {
+        self.radius
+    }
+
+    pub fn code(&self) -> u64 {
+        self.code
+    }
+
+    /// Number of cells in a neighbourhood. 
+    pub fn size(&self) -> usize {
+        2 * self.radius as usize + 1
+    }
+
+    /// Number of digits in the code, i.e. the number of cases the family distinguishes. 
+    pub fn entries(&self) -> u32 {
+        self.checked_entries().expect("checked when the rule was created")
+    }
+
+    /// The rule of the same family, number of states and radius with code `code`. 
+    pub fn with_code(self, code: u64) -> Result<LocalRule, &'static str> {
+        match self.codes() {
+            Some(codes) if code >= codes => Err("Rule code is too large for the family, states and radius"), 
+            _ => Ok(LocalRule { code, ..self }), 
+        }
+    }
+
+    /// The rule with the next code, wrapping around after the last. 
+    pub fn next(self) -> LocalRule {
+        let last = self.codes().expect("checked when the rule was created") - 1;
+        let code = match self.code {
+            code if code == last => 0, 
+            code => code + 1, 
+        };
+        LocalRule { code, ..self }
+    }
+
+    /// The rule with the previous code, wrapping around before the first. 
+    pub fn previous(self) -> LocalRule {
+        let last = self.codes().expect("checked when the rule was created") - 1;
+        LocalRule { code: self.code.checked_sub(1).unwrap_or(last), ..self }
+    }
+
+    /// The rule with digit `entry` of the code increased by one, wrapping around to 0, which flips the bit
+    /// for rules with two states. Codes with fewer entries are returned unchanged. 
+    pub fn flip(self, entry: u32) -> LocalRule {
+        if entry >= self.entries() {
+            return self
+        }
+        let place = u64::from(self.states).pow(entry);
+        let code = match self.digit(entry) + 1 == u64::from(self.states) {
+            true => self.code - (u64::from(self.states) - 1) * place, 
+            false => self.code + place, 
+        };
+        LocalRule { code, ..self }
+    }
+
+    /// Applies the rule to a neighbourhood of [`LocalRule::size`] cells. 
+    pub fn apply(&self, neighbourhood: &[u8]) -> u8 {
+        assert_eq!(neighbourhood.len(), self.size(), "neighbourhood doesn't match the radius");
+        let states = u32::from(self.states);
+        let entry = match self.family {
+            Family::Elementary => neighbourhood
+                .iter()
+                .fold(0, |entry, &cell| entry * states + u32::from(cell)), 
+            Family::Totalistic => sum(neighbourhood), 
+            Family::OuterTotalistic => {
+                let centre = u32::from(neighbourhood[self.radius as usize]);
+                states * (sum(neighbourhood) - centre) + centre
+            }
+        };
+        self.digit(entry) as u8
+    }
+
+    /// The equivalent elementary [`Rule`], if there are two states and the radius is 1. 
+    pub fn elementary(&self) -> Option<Rule> {
+        if self.states != 2 || self.radius != 1 {
+            return None
+        }
+        let code = (0..8)
+            .filter(|n: &u8| self.apply(&[n >> 2 & 1, n >> 1 & 1, n & 1]) != 0)
+            .fold(0, |code, n| code | 1 << n);
+        Some(Rule(code))
+    }
+
+    /// Digit `entry` of the code. 
+    fn digit(&self, entry: u32) -> u64 {
+        let states = u64::from(self.states);
+        self.code / states.pow(entry) % states
+    }
+
+    /// Number of digits in the code, if it fits in a [`u32`]. 
+    fn checked_entries(&self) -> Option<u32> {
+        let states = u32::from(self.states);
+        let size = self.size() as u32;
+        match self.family {
+            Family::Elementary => states.checked_pow(size), 
+            Family::Totalistic => Some((states - 1) * size + 1), 
+            Family::OuterTotalistic => Some(states * ((states - 1) * (size - 1) + 1)), 
+        }
+    }
+
+    /// Number of possible codes, if it fits in a [`u64`]. 
+    fn codes(&self) -> Option<u64> {
+        u64::from(self.states).checked_pow(self.checked_entries()?)
+    }
+}
+
+/// Sum of the states of the cells. 
+fn sum(cells: &[u8]) -> u32 {
+    cells.iter().map(|&cell| u32::from(cell)).sum()
+}
+
+impl From<Rule> for LocalRule {
+    fn from(rule: Rule) -> LocalRule {
+        LocalRule {
+            family: Family::Elementary, 
+            states: 2, 
+            radius: 1, 
+            code: rule.0.into(), 
+        }
+    }
+}
+
+impl Display for LocalRule {
+    /// Formats the rule as e.g. `rule 110`, `totalistic rule 52 (radius 2)` or `3-colour totalistic rule
+    /// 777 (radius 1)`. 
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.states != 2 {
+            write!(f, "{}-colour ", self.states)?;
+        }
+        let family = match self.family {
+            Family::Elementary => "", 
+            Family::Totalistic => "totalistic ", 
+            Family::OuterTotalistic => "outer-totalistic ", 
+        };
+        write!(f, "{family}rule {}", self.code)?;
+        match (self.family, self.radius) {
+            (Family::Elementary, 1) => Ok(()), 
+            (_, radius) => write!(f, " (radius {radius})"), 
+        }
+    }
+}
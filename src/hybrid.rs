@@ -0,0 +1,85 @@
+use std::{fmt, str::FromStr};
+use crate::Rule;
+
+/// Elementary rules of a hybrid automaton, one per cell. The rules are repeated for configurations wider
+/// than the list, so `90,150` alternates between the two rules over any width. 
+///
+/// Parsed from Wolfram codes separated by commas or whitespace, with `<count>*<code>` for runs of the same
+/// rule:
+///
+/// ```
+/// use eca_explorer::{Hybrid, Rule};
+///
+/// let hybrid: Hybrid = "90,2*150".parse().unwrap();
+/// assert_eq!(hybrid.0, vec![Rule(90), Rule(150), Rule(150)]);
+/// assert_eq!(hybrid.rule(4), Rule(150));
+/// assert_eq!(hybrid.regions(7), vec![false, true, true, false, true, true, false]);
+/// assert!("90,256".parse::<Hybrid>().is_err());
+/// assert!("65536*90".parse::<Hybrid>().is_err());
+/// assert_eq!("90,150,150,150".parse::<Hybrid>().unwrap().to_string(), "hybrid 90,3*150");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hybrid(pub Vec<Rule>);
+
+impl Hybrid {
+    /// The rule of the cell at `index`. 
+    pub fn rule(&self, index: usize) -> Rule {
+        self.0[index % self.0.len()]
+    }
+
+    /// For each of `width` cells, whether it lies in an odd-numbered region of neighbouring cells with the
+    /// same rule, counting from 0. Drawing these regions in a second colour shows the rule boundaries. 
+    pub fn regions(&self, width: usize) -> Vec<bool> {
+        let mut odd = false;
+        (0..width)
+            .map(|i| {
+                odd ^= i > 0 && self.rule(i) != self.rule(i - 1);
+                odd
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Hybrid {
+    /// Formats the rules as e.g. `hybrid 90,3*150`, in the syntax they are parsed from. 
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "hybrid ")?;
+        for (i, run) in self.0.chunk_by(|a, b| a == b).enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            match run.len() {
+                1 => write!(f, "{}", run[0].0)?, 
+                count => write!(f, "{count}*{}", run[0].0)?, 
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Hybrid {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<Hybrid, &'static str> {
+        let mut rules = Vec::new();
+        let runs = string
+            .split(|char: char| char == ',' || char.is_whitespace())
+            .filter(|run| !run.is_empty());
+        for run in runs {
+            let (count, code): (usize, _) = match run.split_once('*') {
+                Some((count, code)) => (count.parse().map_err(|_| "Run count must be a number")?, code), 
+                None => (1, run), 
+            };
+            let code = code.parse().map_err(|_| "Rules of a hybrid automaton must be Wolfram codes (0-255)")?;
+            // as many rules as cells at most, like the widest configuration
+            if rules.len().checked_add(count).is_none_or(|len| len > usize::from(u16::MAX)) {
+                return Err("A hybrid automaton can have at most 65535 rules")
+            }
+            rules.extend(std::iter::repeat_n(Rule(code), count));
+        }
+        match rules.is_empty() {
+            true => Err("A hybrid automaton needs at least one rule"), 
+            false => Ok(Hybrid(rules)), 
+        }
+    }
+}
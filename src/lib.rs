@@ -0,0 +1,496 @@
+//! Simulation core of the elementary-cellular-automata explorer. 
+//!
+//! A run is described by [`Settings`] and an initial [`Cells`] configuration. Generations are computed one
+//! at a time with [`step`], or more conveniently by iterating an [`Automaton`]:
+//!
+//! ```
+//! use eca_explorer::{Automaton, Cells, EdgeHandling, Rule, Settings};
+//!
+//! let initial: Cells = "0001000".parse().unwrap();
+//! let settings = Settings::new(Rule(90), EdgeHandling::Wrap);
+//! let rows: Vec<String> = Automaton::new(initial, settings)
+//!     .take(3)
+//!     .map(|cells| cells.to_string())
+//!     .collect();
+//! assert_eq!(rows.len(), 3);
+//! ```
+
+mod basins;
+mod classify;
+mod cycle;
+mod damage;
+mod family;
+mod hybrid;
+mod packed;
+mod pattern;
+mod preimages;
+mod rule;
+mod stats;
+mod stochastic;
+mod update;
+
+use std::{
+    cell::RefCell, 
+    fmt::{self, Display, Formatter}, 
+    iter, 
+    str::FromStr, 
+    time::Duration, 
+};
+use clap::ValueEnum;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub use basins::{Attractor, Basins, StateGraph};
+pub use classify::{classify, Class, Classification};
+pub use cycle::{Cycle, CycleDetector};
+pub use damage::{Damage, Spreading};
+pub use family::{Family, LocalRule, MAX_STATES};
+pub use hybrid::Hybrid;
+pub use packed::PackedCells;
+pub use pattern::Pattern;
+pub use preimages::Preimages;
+pub use stats::{block_entropy, Statistics};
+pub use stochastic::{ProbabilityTable, Stochastic};
+pub use update::Update;
+
+/// Rule composed of a boolean outcome for all 8 possible 3-cell neighbourhood combinations. Represented as
+/// its Wolfram code. 
+///
+/// ```
+/// use eca_explorer::Rule;
+///
+/// // rule 90 is the XOR of the left and right neighbours
+/// assert!(Rule(90).apply([true, false, false]));
+/// assert!(!Rule(90).apply([true, true, true]));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule(pub u8);
+
+impl Rule {
+    /// Applies the rule to a neighbourhood by checking the value of the nth bit, where `n` is the 3-bit
+    /// integer contained in `neighbourhood`. 
+    pub fn apply(&self, neighborhood: [bool; 3]) -> bool {
+        let [n3, n2, n1] = neighborhood.map(u8::from);
+        self.0 & (1 << n1 << (n2 << 1) << (n3 << 2)) != 0
+    }
+}
+
+/// The sequence of cells getting updated, each holding a small integer state. State `0` is dead, and any
+/// other state counts as alive; rules with two states only use `0` and `1`. 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cells(pub Vec<u8>);
+
+impl Cells {
+    /// Creates a configuration of `width` cells, each of which is alive with probability `density`. 
+    ///
+    /// ```
+    /// use eca_explorer::Cells;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let cells = Cells::new_random(8, 1.0, &mut StdRng::seed_from_u64(0));
+    /// assert_eq!(cells, "11111111".parse().unwrap());
+    /// ```
+    pub fn new_random(width: u16, density: f64, rng: &mut impl Rng) -> Cells {
+        Cells::new_random_states(width, 2, density, rng)
+    }
+
+    /// Creates a configuration of `width` cells with the given number of states, each of which is alive with
+    /// probability `density`, in a state drawn uniformly from the live ones. 
+    pub fn new_random_states(width: u16, states: u8, density: f64, rng: &mut impl Rng) -> Cells {
+        let cells = (0..width)
+            .map(|_| match rng.gen_bool(density) {
+                true => rng.gen_range(1..states.max(2)), 
+                false => 0, 
+            })
+            .collect();
+        Cells(cells)
+    }
+
+    /// The highest state of any cell. 
+    pub fn max_state(&self) -> u8 {
+        self.0.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Iterator over all 3-cell neighbourhoods, with live cells as `true`. 
+    fn neighborhoods(&self) -> impl Iterator<Item = [bool; 3]> + '_ {
+        self.0
+            .windows(3)
+            .map(|window| [window[0], window[1], window[2]].map(|cell| cell != 0))
+    }
+
+    // Returns `[first two cells, last two cells]`, with live cells as `true`
+    fn edges(&self) -> [[bool; 2]; 2] {
+        [self.0.first_chunk::<2>(), self.0.last_chunk::<2>()]
+            .map(|x| x.expect("There are at least 2 cells"))
+            .map(|x| x.map(|cell| cell != 0))
+    }
+}
+
+impl FromStr for Cells {
+    type Err = &'static str;
+
+    /// Parses a sequence of digits as a cell configuration, one state per digit. 
+    ///
+    /// ```
+    /// use eca_explorer::Cells;
+    ///
+    /// let cells: Cells = "0120".parse().unwrap();
+    /// assert_eq!(cells, Cells(vec![0, 1, 2, 0]));
+    /// assert!("01".parse::<Cells>().is_err());
+    /// assert!("01x0".parse::<Cells>().is_err());
+    /// ```
+    fn from_str(string: &str) -> Result<Cells, &'static str> {
+        if string.len() < 3 {
+            return Err("Initial configuration must be at least 3 cells wide")
+        }
+        string.chars()
+            .map(|char| char.to_digit(10).map(|digit| digit as u8))
+            .collect::<Option<_>>()
+            .map(Cells)
+            .ok_or("Initial configuration must only contain digits")
+    }
+}
+
+impl Display for Cells {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let string: String = self.0.iter()
+            .map(|cell| match cell {
+                0 => "╶╴", 
+                1 => "██", 
+                // without colours, higher states are told apart by shading
+                state => ["▓▓", "▒▒", "░░"][(*state as usize - 2) % 3], 
+            })
+            .collect();
+        write!(f, "{string}")
+    }
+}
+
+/// How new values for cells at the very edges should be computed. 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeHandling {
+    /// The previous edge values are retained. 
+    Copy, 
+    /// Edge neighbours are set to `0`. 
+    Crop, 
+    /// Edge neighbours wrap around to the other side. 
+    Wrap, 
+}
+
+/// Settings used to run the ECA. 
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub rule: LocalRule, 
+    pub edge_handling: EdgeHandling, 
+    /// Number of generations a front-end should run for. Not used by [`step`] or [`Automaton`]. 
+    pub generations: u16, 
+    /// Delay a front-end should wait for between generations. Not used by [`step`] or [`Automaton`]. 
+    pub delay: Duration, 
+    /// Whether the rule is second-order (Fredkin's construction): each generation is also combined with
+    /// the one before the current, by subtracting its states modulo the number of states (XOR for two
+    /// states). Any rule becomes reversible this way. 
+    pub second_order: bool, 
+    /// Noise and probabilistic rules, if any. 
+    pub stochastic: Option<Stochastic>, 
+    /// Scheme by which the cells of each generation are updated. 
+    pub update: Update, 
+    /// Rules of each cell in a hybrid automaton, replacing `rule`, if any. Only used if `rule` is
+    /// elementary. 
+    pub hybrid: Option<Hybrid>, 
+    /// Generator choosing the cells updated by a random update scheme, like the one of [`Stochastic`]. 
+    update_rng: RefCell<StdRng>, 
+}
+
+impl Settings {
+    /// Settings for running `rule` with the given edge handling, without any generation limit or delay. 
+    pub fn new(rule: impl Into<LocalRule>, edge_handling: EdgeHandling) -> Settings {
+        Settings {
+            rule: rule.into(), 
+            edge_handling, 
+            generations: u16::MAX, 
+            delay: Duration::ZERO, 
+            second_order: false, 
+            stochastic: None, 
+            update: Update::Synchronous, 
+            hybrid: None, 
+            update_rng: RefCell::new(StdRng::seed_from_u64(0)), 
+        }
+    }
+
+    /// The settings with the given update scheme, choosing the cells updated by a random scheme with a
+    /// generator seeded with `seed`. Settings from [`Settings::new`] choose them with seed 0. 
+    pub fn with_update(self, update: Update, seed: u64) -> Settings {
+        Settings {
+            update, 
+            update_rng: RefCell::new(StdRng::seed_from_u64(seed)), 
+            ..self
+        }
+    }
+
+    /// The elementary rule applied to the cell at each index, if `rule` is elementary: the cell's rule in a
+    /// hybrid automaton, or else `rule` itself. Working out the elementary rule takes a few steps, so this is
+    /// meant to be called once per generation rather than once per cell. 
+    pub fn elementary_rules(&self) -> Option<impl Fn(usize) -> Rule + '_> {
+        let rule = self.rule.elementary()?;
+        Some(move |index| match &self.hybrid {
+            Some(hybrid) => hybrid.rule(index), 
+            None => rule, 
+        })
+    }
+}
+
+/// A representation of a cell configuration for which the next generation can be computed. Implemented by
+/// [`Cells`] and by the bit-packed [`PackedCells`]. 
+pub trait Storage: Clone {
+    /// Computes the next generation of `self` into `next`. For [second-order](Settings::second_order) rules, 
+    /// `next` must hold the generation before `self`, which is combined into the result; otherwise its
+    /// previous contents are discarded. 
+    fn step_into(&self, next: &mut Self, settings: &Settings);
+}
+
+impl Storage for Cells {
+    fn step_into(&self, next: &mut Cells, settings: &Settings) {
+        if settings.update != Update::Synchronous {
+            return self.update_into(next, settings, &mut Vec::new())
+        }
+        let Some(rules) = settings.elementary_rules() else {
+            return next.store(self.step_wide(settings), settings)
+        };
+        let width = self.0.len();
+        let apply = |index, neighborhood| {
+            let rule = rules(index);
+            match &settings.stochastic {
+                Some(stochastic) => stochastic.apply(rule, neighborhood), 
+                None => rule.apply(neighborhood), 
+            }
+        };
+        let [left_edge, right_edge] = {
+            let [[l1, l2], [r1, r2]] = self.edges();
+
+            match settings.edge_handling {
+                EdgeHandling::Copy => [l1, r2], 
+                EdgeHandling::Crop => [
+                    apply(0, [false, l1, l2]), 
+                    apply(width - 1, [r1, r2, false]), 
+                ], 
+                EdgeHandling::Wrap => [
+                    apply(0, [r2, l1, l2]), 
+                    apply(width - 1, [r1, r2, l1]), 
+                ], 
+            }
+        };
+        let [left_edge, right_edge] = [left_edge, right_edge]
+            .map(iter::once);
+        let middle = self
+            .neighborhoods()
+            .enumerate()
+            .map(|(i, neighborhood)| apply(i + 1, neighborhood));
+        let cells = left_edge
+            .chain(middle)
+            .chain(right_edge)
+            .map(u8::from);
+
+        next.store(cells, settings);
+
+        assert_eq!(self.0.len(), next.0.len());
+    }
+}
+
+impl Cells {
+    /// The next generation under a rule with a radius above 1 or more than two states. With
+    /// [`EdgeHandling::Copy`], the cells whose neighbourhood extends beyond the edges keep their values, 
+    /// without noise. 
+    fn step_wide<'a>(&'a self, settings: &'a Settings) -> impl Iterator<Item = u8> + 'a {
+        let rule = settings.rule;
+        let radius = rule.radius() as isize;
+        let width = self.0.len() as isize;
+        let mut neighbourhood = vec![0; rule.size()];
+
+        (0..width).map(move |i| {
+            let inside = i >= radius && i + radius < width;
+            if !inside && settings.edge_handling == EdgeHandling::Copy {
+                return self.0[i as usize]
+            }
+            for (index, cell) in (i - radius..=i + radius).zip(&mut neighbourhood) {
+                *cell = match ((0..width).contains(&index), settings.edge_handling) {
+                    (true, _) => self.0[index as usize], 
+                    (false, EdgeHandling::Wrap) => self.0[index.rem_euclid(width) as usize], 
+                    (false, _) => 0, 
+                };
+            }
+            match &settings.stochastic {
+                Some(stochastic) => stochastic.perturb(rule.apply(&neighbourhood), rule.states()), 
+                None => rule.apply(&neighbourhood), 
+            }
+        })
+    }
+
+    /// Overwrites `self` with the computed `cells`, or for second-order rules, subtracts its states from
+    /// them. 
+    fn store(&mut self, cells: impl Iterator<Item = u8>, settings: &Settings) {
+        match settings.second_order {
+            true => {
+                let states = settings.rule.states();
+                for (previous, cell) in self.0.iter_mut().zip(cells) {
+                    *previous = (cell + states - *previous) % states;
+                }
+            }
+            false => {
+                self.0.clear();
+                self.0.extend(cells);
+            }
+        }
+    }
+}
+
+/// Computes the next generation from `front` into `back`. Returns `(new front, new back)`. 
+///
+/// The previous contents of `back` are discarded, but its allocation is reused. For
+/// [second-order](Settings::second_order) rules, `back` must instead hold the generation before `front`, so
+/// that the new back is the previous generation of the new front again. 
+///
+/// ```
+/// use eca_explorer::{step, Cells, EdgeHandling, Rule, Settings};
+///
+/// let settings = Settings::new(Rule(90), EdgeHandling::Crop);
+/// let front: Cells = "00100".parse().unwrap();
+/// let back = front.clone();
+/// let (front, _) = step(front, back, &settings);
+/// assert_eq!(front, "01010".parse().unwrap());
+/// ```
+pub fn step<C: Storage>(front: C, mut back: C, settings: &Settings) -> (C, C) {
+    front.step_into(&mut back, settings);
+    (back, front)
+}
+
+
+/// Owns the double buffers of a running ECA and computes one generation at a time. 
+///
+/// Iterating an automaton yields the current generation before advancing, starting with the initial
+/// configuration. Since an [`Iterator`] cannot lend out references into itself, each item is a copy; use
+/// [`Automaton::current`] and [`Automaton::advance`] to avoid the allocation. The cells can be stored in any
+/// [`Storage`], such as [`PackedCells`] for very wide runs. 
+///
+/// ```
+/// use eca_explorer::{Automaton, Cells, EdgeHandling, Rule, Settings};
+///
+/// let settings = Settings::new(Rule(90), EdgeHandling::Wrap);
+/// let initial: Cells = "0001000".parse().unwrap();
+/// let mut automaton = Automaton::new(initial, settings);
+/// assert_eq!(automaton.advance(), &"0010100".parse::<Cells>().unwrap());
+/// assert_eq!(automaton.generation(), 1);
+/// assert_eq!(automaton.nth(1), Some("0100010".parse().unwrap()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Automaton<C: Storage = Cells> {
+    /// Allocates the current generation. 
+    front: C, 
+    /// Allocates the next generation, and holds the previous one in between. 
+    back: C, 
+    settings: Settings, 
+    generation: u64, 
+}
+
+impl<C: Storage> Automaton<C> {
+    /// Creates an automaton starting from the `initial` configuration. For second-order rules, the
+    /// generation before it is taken to be the same. 
+    pub fn new(initial: C, settings: Settings) -> Automaton<C> {
+        Automaton::with_previous(initial.clone(), initial, settings)
+    }
+
+    /// Creates an automaton starting from the `initial` configuration, preceded by `previous`, which only
+    /// matters for [second-order](Settings::second_order) rules. 
+    ///
+    /// ```
+    /// use eca_explorer::{Automaton, Cells, EdgeHandling, Rule, Settings};
+    ///
+    /// let mut settings = Settings::new(Rule(30), EdgeHandling::Wrap);
+    /// settings.second_order = true;
+    /// let previous: Cells = "0000000".parse().unwrap();
+    /// let initial: Cells = "0001000".parse().unwrap();
+    /// let mut automaton = Automaton::with_previous(previous.clone(), initial.clone(), settings);
+    /// automaton.nth(9);
+    /// // second-order rules are reversible, so running backwards returns to the initial pair
+    /// automaton.reverse();
+    /// automaton.nth(9);
+    /// assert_eq!((automaton.current(), automaton.previous()), (&previous, &initial));
+    /// ```
+    pub fn with_previous(previous: C, initial: C, settings: Settings) -> Automaton<C> {
+        Automaton {
+            back: previous, 
+            front: initial, 
+            settings, 
+            generation: 0, 
+        }
+    }
+
+    /// The current generation. 
+    pub fn current(&self) -> &C {
+        &self.front
+    }
+
+    /// The generation before the current one, or a copy of the initial configuration before any have been
+    /// computed. 
+    pub fn previous(&self) -> &C {
+        &self.back
+    }
+
+    /// Number of generations computed so far. 
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Mutable access to the settings, e.g. to change the rule while running. 
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Restarts from the `initial` configuration, keeping the settings. 
+    pub fn reset(&mut self, initial: C) {
+        self.reset_with_previous(initial.clone(), initial);
+    }
+
+    /// Restarts from the `initial` configuration preceded by `previous`, keeping the settings. 
+    pub fn reset_with_previous(&mut self, previous: C, initial: C) {
+        self.back = previous;
+        self.front = initial;
+        self.generation = 0;
+    }
+
+    /// Swaps the current and previous generations. Second-order rules are symmetric in time, so advancing
+    /// afterwards runs the evolution backwards. The generation count keeps increasing. 
+    pub fn reverse(&mut self) {
+        std::mem::swap(&mut self.front, &mut self.back);
+    }
+
+    /// Computes the next generation and returns it. 
+    pub fn advance(&mut self) -> &C {
+        self.front.step_into(&mut self.back, &self.settings);
+        std::mem::swap(&mut self.front, &mut self.back);
+        self.generation += 1;
+        &self.front
+    }
+}
+
+impl Automaton<Cells> {
+    /// Computes the next generation like [`Automaton::advance`], setting `updated` to whether each cell was
+    /// updated under the [update scheme](Settings::update). 
+    pub fn advance_marked(&mut self, updated: &mut Vec<bool>) -> &Cells {
+        self.front.update_into(&mut self.back, &self.settings, updated);
+        std::mem::swap(&mut self.front, &mut self.back);
+        self.generation += 1;
+        &self.front
+    }
+}
+
+impl<C: Storage> Iterator for Automaton<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<C> {
+        let current = self.front.clone();
+        self.advance();
+        Some(current)
+    }
+}
@@ -1,249 +1,594 @@
+mod commands;
+mod output;
+
 use std::{
-    fmt::{self, Display, Formatter}, 
-    io, 
-    iter, 
-    str::FromStr, 
-    time::Duration, 
-};
-use clap::{arg, Parser, ValueEnum};
-use crossterm::{
-    cursor::{Hide, Show}, 
-    event::Event, 
-    style::Print, 
-    terminal::{EnterAlternateScreen, LeaveAlternateScreen}, 
+    fs::{self, File}, 
+    io::{self, BufWriter, IsTerminal, Write}, 
+    path::PathBuf, 
+    time::{Duration, Instant}, 
 };
+use clap::{Args, Parser, Subcommand};
+use eca_explorer::{Automaton, Cells, Cycle, CycleDetector, EdgeHandling, Family, Hybrid, LocalRule, Pattern, ProbabilityTable, Settings, Stochastic, Update, MAX_STATES};
 use main_error::MainResult;
-use rand::Rng;
-
-/// Rule composed of a boolean outcome for all 8 possible 3-cell neighbourhood combinations. Represented as
-/// its Wolfram code. 
-#[derive(Clone, Copy)]
-struct Rule(u8);
-
-impl Rule {
-    /// Applies the rule to a neighbourhood by checking the value of the nth bit, where `n` is the 3-bit
-    /// integer contained in `neighbourhood`. 
-    fn apply(&self, neighborhood: [bool; 3]) -> bool {
-        let [n3, n2, n1] = neighborhood.map(u8::from);
-        self.0 & (1 << n1 << (n2 << 1) << (n3 << 2)) != 0
-    }
-}
-
-/// The sequence of cells getting updated. 
-#[derive(Clone, Debug, PartialEq)]
-struct Cells(Vec<bool>);
-
-impl Cells {
-    fn new_random(width: u16) -> Cells {
-        let mut cells = vec![false; width as usize];
-        let mut rng = rand::thread_rng();
-        rng.fill(&mut cells[..]);
-        Cells(cells)
-    }
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use output::{Bitmap, Colour, Command, Format, Gif, Graymap, Image, Plain, Sink, StatsFile, Status, Svg, Symbols, Terminal, svg};
 
-    /// Iterator over all 3-cell neighbourhoods. 
-    fn neighborhoods(&self) -> impl Iterator<Item = [bool; 3]> + '_ {
-        self.0
-            .windows(3)
-            .map(TryInto::try_into)
-            .map(Result::unwrap)
-    }
-
-    // Returns `[first two cells, last two cells]`
-    fn edges(&self) -> [[bool; 2]; 2] {
-        [self.0.first_chunk::<2>(), self.0.last_chunk::<2>()]
-            .map(|x| x.copied())
-            .map(|x| x.expect("There are at least 2 cells"))
-    }
-}
-
-impl FromStr for Cells {
-    type Err = &'static str;
-
-    /// Parses a sequence of ones and zeroes as a cell configuration. 
-    fn from_str(string: &str) -> Result<Cells, &'static str> {
-        if string.len() < 3 {
-            return Err("Initial configuration must be at least 3 cells wide")
-        }
-        string.chars()
-            .map(|char| match char {
-                '0' => Some(false), 
-                '1' => Some(true), 
-                _ => None, 
-            })
-            .collect::<Option<_>>()
-            .map(Cells)
-            .ok_or("Initial configuration must only contain '0' or '1'")
-    }
-}
+/// Run an elementary (one-dimensional) cellular automaton in your terminal. 
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands=true, subcommand_negates_reqs=true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>, 
 
-impl Display for Cells {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let string: String = self.0.iter()
-            .map(|cell| match cell {
-                false => "╶╴", 
-                true => "██", 
-            })
-            .collect();
-        write!(f, "{string}")
-    }
+    #[command(flatten)]
+    run: Option<RunArgs>, 
 }
 
-/// How new values for cells at the very edges should be computed. 
-#[derive(ValueEnum, Clone, Copy, Debug)]
-enum EdgeHandling {
-    /// The previous edge values are retained. 
-    Copy, 
-    /// Edge neighbours are set to `0`. 
-    Crop, 
-    /// Edge neighbours wrap around to the other side. 
-    Wrap, 
+#[derive(Subcommand)]
+enum Commands {
+    Info(commands::Info), 
+    Atlas(commands::Atlas), 
+    Basins(commands::Basins), 
+    Classify(commands::Classify), 
+    Damage(commands::Damage), 
+    Preimages(commands::Preimages), 
+    Reverse(commands::Reverse), 
 }
 
-/// Run an elementary (one-dimensional) cellular automaton in your terminal. 
-#[derive(Parser)]
-struct Cli {
-    /// The Wolfram code (0-255) of the rule. 
-    rule: u8, 
+/// Arguments for running the ECA. 
+#[derive(Args)]
+struct RunArgs {
+    /// The code of the rule, read according to `--family` and `--states`. For elementary rules with two
+    /// states and radius 1 this is the Wolfram code (0-255). 
+    rule: u64, 
 
     /// Initial cell configuration. If not specified, a random configuration with the same printed width as
     /// the terminal is used. 
-    initial: Option<Cells>, 
+    ///
+    /// Besides a sequence of ones and zeroes, the following patterns are accepted: run-length forms such as
+    /// `27*0,1,27*0`, hex (`hex:1f0` or `0x1f0`) and base64 (`base64:8A==`) encodings, `single` for a
+    /// single centred cell, `center:<cells>`, `repeat:<cells>` and `random:<density>`. 
+    initial: Option<Pattern>, 
+
+    /// Width of the initial configuration. Patterns are padded with dead cells (or centred, repeated, etc.)
+    /// to this width. Required if not running in a terminal and the pattern has no width of its own. 
+    #[arg(long, short, value_parser=clap::value_parser!(u16).range(3..))]
+    width: Option<u16>, 
 
     /// How the two edges are handled. 
     #[arg(long, short, default_value="wrap")]
     edges: EdgeHandling, 
 
-    /// Number of generations to run for. If not specified, the terminal height is used. 
+    /// How the code of the rule is read. 
+    #[arg(long, default_value="elementary")]
+    family: Family, 
+
+    /// Number of states (2-10) a cell can be in. The code of the rule is read as a number in this base, and
+    /// cells in states above 1 are shown in a palette of colours. 
+    #[arg(long, default_value_t=2, value_parser=clap::value_parser!(u8).range(2..=MAX_STATES as i64))]
+    states: u8, 
+
+    /// Run the rule as a second-order rule (Fredkin's construction), which makes it reversible: each new
+    /// generation is the rule's output minus the generation before the current one, i.e. XOR for two states. 
+    #[arg(long)]
+    second_order: bool, 
+
+    /// Generation preceding the initial configuration for `--second-order`, in the same syntax and with the
+    /// same width. If not specified, all cells are dead. 
+    #[arg(long, requires="second_order")]
+    previous: Option<Pattern>, 
+
+    /// Number of cells on either side of a cell that its new value depends on. 
+    #[arg(long, default_value_t=1)]
+    radius: u8, 
+
+    /// Seed for the random number generator used for random configurations. If not specified, a random
+    /// seed is used, which is shown in the status line and printed on exit. 
+    #[arg(long)]
+    seed: Option<u64>, 
+
+    /// Probability (0.0-1.0) that each cell in a random configuration is alive. 
+    #[arg(long, default_value_t=0.5, value_parser=parse_density)]
+    density: f64, 
+
+    /// Probability (0.0-1.0) that each computed cell is flipped. The noise is drawn from the seeded random
+    /// number generator, so that the run can be replayed. 
+    #[arg(long, value_parser=parse_density)]
+    noise: Option<f64>, 
+
+    /// Probabilities (0.0-1.0) that a cell becomes alive for each of the 8 neighbourhoods `000` to `111`, 
+    /// comma-separated, replacing the elementary rule. Drawn from the seeded random number generator. 
+    #[arg(long)]
+    prob_table: Option<ProbabilityTable>, 
+
+    /// How the cells of each generation are updated: `synchronous`, `sweep` (one after another from left to
+    /// right), `random:<cells>` (that many cells chosen at random, one after another), `block:<size>` (blocks
+    /// of neighbouring cells one after another) or `async:<probability>` (each cell with that probability, 
+    /// all at once). Except for `synchronous`, the updated cells are marked in the terminal and PNG images. 
+    #[arg(long, default_value="synchronous")]
+    update: Update, 
+
+    /// Rules of a hybrid automaton, one per cell, replacing the elementary rule: Wolfram codes separated by
+    /// commas, such as `90,150`, with `<count>*<code>` for runs of the same rule. The rules are repeated to
+    /// the width of the configuration. 
+    #[arg(long, conflicts_with="hybrid_file")]
+    hybrid: Option<Hybrid>, 
+
+    /// File with the rules of a hybrid automaton, in the same syntax as `--hybrid`, also accepting
+    /// whitespace and newlines between the rules. 
+    #[arg(long)]
+    hybrid_file: Option<PathBuf>, 
+
+    /// Show the rule boundaries of a hybrid automaton by drawing every other region of neighbouring cells
+    /// with the same rule in a second colour, in the terminal and PNG images. 
+    #[arg(long)]
+    boundaries: bool, 
+
+    /// Number of generations to run for. If not specified, the terminal height is used. Required if not
+    /// running in a terminal. 
     #[arg(long, short)]
     generations: Option<u16>, 
 
     /// Number of milliseconds to delay before the next generation is computed. 
     #[arg(long, short)]
     delay: Option<u64>, 
+
+    /// Write the spacetime diagram to a file instead of the terminal. The format is given by `--format`, or
+    /// guessed from the file extension (`.png`, `.pbm` or `.pgm`). 
+    #[arg(long, short)]
+    output: Option<PathBuf>, 
+
+    /// Format of the spacetime diagram. If given without `--output`, the diagram is written to stdout. 
+    #[arg(long, short, conflicts_with_all=["gif", "svg"])]
+    format: Option<Format>, 
+
+    /// Write the evolution to an animated GIF file instead of the terminal. Each frame shows a window of the
+    /// most recent generations, and is shown for the `--delay` (in steps of 10 milliseconds). 
+    #[arg(long, conflicts_with="output")]
+    gif: Option<PathBuf>, 
+
+    /// Number of generations shown in each frame of the GIF file. If not specified, the frames are square. 
+    #[arg(long, requires="gif", value_parser=clap::value_parser!(u16).range(1..))]
+    window: Option<u16>, 
+
+    /// Write the spacetime diagram to an SVG file instead of the terminal. 
+    #[arg(long, conflicts_with_all=["output", "gif"])]
+    svg: Option<PathBuf>, 
+
+    /// Draw grid lines between the cells in the SVG file. 
+    #[arg(long, requires="svg")]
+    grid: bool, 
+
+    /// Show the rule, or the rules of a hybrid automaton, in a caption below the diagram in the SVG file. 
+    #[arg(long, requires="svg")]
+    caption: bool, 
+
+    /// Width and height in pixels of each cell in image files. 
+    #[arg(long, default_value_t=4, value_parser=clap::value_parser!(u16).range(1..))]
+    cell_size: u16, 
+
+    /// Colour of live cells in image files. 
+    #[arg(long, default_value="#000000")]
+    alive_colour: Colour, 
+
+    /// Colour of dead cells in image files. 
+    #[arg(long, default_value="#ffffff")]
+    dead_colour: Colour, 
+
+    /// Colour that updated cells are tinted with in PNG images, for update schemes other than synchronous. 
+    #[arg(long, default_value="#ff0000")]
+    updated_colour: Colour, 
+
+    /// Colour that every other region of a hybrid automaton is tinted with in PNG images, for
+    /// `--boundaries`. 
+    #[arg(long, default_value="#0000ff")]
+    boundary_colour: Colour, 
+
+    /// Print each generation as a plain line of characters, without raw mode or an alternate screen. This is
+    /// the default when stdout is not a terminal. 
+    #[arg(long, short)]
+    plain: bool, 
+
+    /// The two characters used for dead and alive cells in plain output, e.g. `.#`. 
+    #[arg(long, default_value="01")]
+    symbols: Symbols, 
+
+    /// Print a summary of the run on exit, including the transient length and period of the cycle it
+    /// entered, if any. With `--edges wrap`, cycles that shift in space are also detected. Runs with noise, 
+    /// a probability table or random updates can't be said to cycle, so no cycles are detected in them. 
+    #[arg(long)]
+    report: bool, 
+
+    /// End the run as soon as it enters a cycle. Not available for runs with noise, a probability table or
+    /// random updates. 
+    #[arg(long)]
+    stop_on_cycle: bool, 
+
+    /// Record statistics of each generation to a CSV file: the density of live cells, block entropies, the
+    /// number and mean length of runs of live cells, and the Hamming distance to the previous generation. 
+    #[arg(long)]
+    stats: Option<PathBuf>, 
+
+    /// Largest block size (1-16) to record the block entropy for in the statistics. 
+    #[arg(long, default_value_t=4, requires="stats", value_parser=clap::value_parser!(u8).range(1..=16))]
+    entropy_blocks: u8, 
 }
 
-/// Settings used to run the ECA. 
-struct Settings {
-    rule: Rule, 
-    edge_handling: EdgeHandling, 
-    generations: u16, 
-    delay: Duration, 
+/// Parses a probability in the range 0.0-1.0. 
+fn parse_density(string: &str) -> Result<f64, String> {
+    let density: f64 = string.parse().map_err(|_| format!("'{string}' is not a number"))?;
+    match (0.0..=1.0).contains(&density) {
+        true => Ok(density), 
+        false => Err(format!("{density} is not in 0.0..=1.0")), 
+    }
 }
 
-/// Computes the next generation from `front` into `back`. Returns `(new front, new back)`. 
-fn step(front: Cells, mut back: Cells, settings: &Settings) -> (Cells, Cells) {
-    let rule = settings.rule;
-    let [left_edge, right_edge] = {
-        let [[l1, l2], [r1, r2]] = front.edges();
-
-        match settings.edge_handling {
-            EdgeHandling::Copy => [l1, r2], 
-            EdgeHandling::Crop => [
-                rule.apply([false, l1, l2]), 
-                rule.apply([r1, r2, false]), 
-            ], 
-            EdgeHandling::Wrap => [
-                rule.apply([r2, l1, l2]), 
-                rule.apply([r1, r2, l1]), 
-            ], 
+/// Seeded source of random cell configurations, so that any run can be replayed. 
+struct Random {
+    seed: u64, 
+    density: f64, 
+    /// Number of states of the rule, which live cells are drawn from. 
+    states: u8, 
+    rng: StdRng, 
+}
+
+impl Random {
+    fn new(seed: u64, density: f64, states: u8) -> Random {
+        Random {
+            seed, 
+            density, 
+            states, 
+            rng: StdRng::seed_from_u64(seed), 
         }
-    };
-    let [left_edge, right_edge] = [left_edge, right_edge]
-        .map(iter::once);
-    let middle = front
-        .neighborhoods()
-        .map(|neighborhood| rule.apply(neighborhood));
-    let cells = left_edge
-        .chain(middle)
-        .chain(right_edge);
+    }
 
-    back.0.clear();
-    back.0.extend(cells);
+    fn cells(&mut self, width: u16) -> Cells {
+        Cells::new_random_states(width, self.states, self.density, &mut self.rng)
+    }
+}
 
-    assert_eq!(front.0.len(), back.0.len());
+/// Outcome of a run, summarised by `--report`. 
+struct Report {
+    /// Number of generations written, including those before any restart. 
+    generations: u64, 
+    /// The cycle entered since the last restart or change of rule, if any. 
+    cycle: Option<Cycle>, 
+}
 
-    (back, front)
+/// The state of a second-order automaton: the previous generation followed by the current one. 
+fn pair(automaton: &Automaton) -> Cells {
+    Cells([automaton.previous().0.as_slice(), &automaton.current().0].concat())
 }
 
+/// Smallest non-zero delay between generations when changing it live. 
+const MIN_DELAY: Duration = Duration::from_millis(10);
+
 /// Runs all generations of the ECA using double-buffering to minimize allocations (mostly for style points; 
 /// the printing of each generation is going to be the bottle-neck, anyways). 
-fn run(initial: Cells, settings: Settings) -> io::Result<()> {
-    // front allocates the current generation; back allocates the next one
-    let mut front = initial;
-    let mut back = front.clone();
-
-    for _ in 0..settings.generations {
-        // print current generation. explicit `\r` is needed in raw mode
-        let string = format!("\n\r{front}");
-        crossterm::execute!{
-            io::stdout(), 
-            Print(string), 
-        }?;
-
-        // compute next generation and swap buffers
-        (front, back) = step(front, back, &settings);
-        
-        // end run prematurely if user presses a key (this also delays)
-        if crossterm::event::poll(settings.delay)? {
-            let _ = crossterm::event::read();
+fn run(
+    mut automaton: Automaton, 
+    random: &mut Random, 
+    detect: bool, 
+    stop_on_cycle: bool, 
+    mut stats: Option<StatsFile>, 
+    sink: &mut dyn Sink, 
+) -> io::Result<Report> {
+    let generations = automaton.settings().generations.into();
+    let second_order = automaton.settings().second_order;
+    let marked = automaton.settings().update != Update::Synchronous;
+    // the cells updated in computing the current generation; empty for the initial one
+    let mut updated = Vec::new();
+    let mut paused = false;
+    // second-order states are pairs of generations, whose rotations aren't rotations of the concatenation, 
+    // and rotations under hybrid rules or updates that aren't synchronous (such as a sweep from the left
+    // edge) don't evolve like the rotated configurations
+    let rotations_evolve = !second_order && automaton.settings().hybrid.is_none() && !marked;
+    let mut detector = CycleDetector::new(match rotations_evolve {
+        true => automaton.settings().edge_handling, 
+        false => EdgeHandling::Crop, 
+    });
+    let mut written = 0;
+    // the rule used to compute the generation last written, to mark where it changed
+    let mut written_rule = automaton.settings().rule;
+    // a hybrid automaton is labelled by its own rules, as they replace the rule for the whole run
+    let hybrid = automaton.settings().hybrid.as_ref().map(Hybrid::to_string);
+    let mut code = hybrid.clone().unwrap_or_else(|| written_rule.code().to_string());
+
+    'run: while automaton.generation() < generations {
+        // output current generation
+        let rule = automaton.settings().rule;
+        if rule != written_rule {
+            sink.divider(rule)?;
+            written_rule = rule;
+            code = rule.code().to_string();
+            // the generations seen under the previous rule say nothing about the new one
+            detector.reset();
+        }
+        match updated.is_empty() {
+            true => sink.write(automaton.current())?, 
+            false => sink.write_marked(automaton.current(), &updated)?, 
+        }
+        if let Some(stats) = &mut stats {
+            stats.record(automaton.generation(), &code, automaton.current())?;
+        }
+        written += 1;
+        let entered = detector.cycle().is_none();
+        let cycle = match (detect, second_order) {
+            (false, _) => None, 
+            (true, true) => detector.push(automaton.generation(), &pair(&automaton)), 
+            (true, false) => detector.push(automaton.generation(), automaton.current()), 
+        };
+        if stop_on_cycle && entered && cycle.is_some() {
             break
         }
-    }
 
-    // wait for user input before exiting
-    loop {
-        if let Event::Key(_) = crossterm::event::read()? {
-            break
+        // handle commands until the next generation is due (this also delays)
+        let due = Instant::now() + automaton.settings().delay;
+        loop {
+            let settings = automaton.settings();
+            sink.status(&Status {
+                rule: hybrid.clone().unwrap_or_else(|| settings.rule.to_string()), 
+                generation: automaton.generation(), 
+                delay: settings.delay, 
+                seed: random.seed, 
+                paused, 
+                cycle, 
+            })?;
+            let timeout = match paused {
+                true => None, 
+                false => Some(due.saturating_duration_since(Instant::now())), 
+            };
+            let Settings { rule, delay, hybrid, .. } = automaton.settings_mut();
+            match sink.command(timeout)? {
+                None => break, 
+                Some(Command::Quit) => break 'run, 
+                Some(Command::Pause) => {
+                    paused = !paused;
+                    if !paused {
+                        break
+                    }
+                }
+                Some(Command::Step) => if paused {
+                    break
+                }
+                Some(Command::Slower) => *delay = (*delay * 2).max(MIN_DELAY), 
+                Some(Command::Faster) => *delay = match *delay / 2 {
+                    delay if delay < MIN_DELAY => Duration::ZERO, 
+                    delay => delay, 
+                }, 
+                Some(Command::Restart) => {
+                    let width = automaton.current().0.len();
+                    // second-order rules restart from a dead previous generation
+                    let cells = random.cells(width
+                        .try_into()
+                        .expect("patterns are built at most `u16::MAX` cells wide"));
+                    automaton.reset_with_previous(Cells(vec![0; width]), cells);
+                    updated.clear();
+                    detector.reset();
+                    if let Some(stats) = &mut stats {
+                        stats.reset();
+                    }
+                    continue 'run
+                }
+                // the rules of a hybrid automaton replace the rule, so changing it would change nothing
+                Some(Command::NextRule | Command::PreviousRule | Command::FlipBit(_) | Command::SetCode(_))
+                    if hybrid.is_some() => {}
+                Some(Command::NextRule) => *rule = rule.next(), 
+                Some(Command::PreviousRule) => *rule = rule.previous(), 
+                Some(Command::FlipBit(bit)) => *rule = rule.flip(bit.into()), 
+                // codes too large for the family are ignored
+                Some(Command::SetCode(code)) => *rule = rule.with_code(code).unwrap_or(*rule), 
+            }
         }
+
+        // compute next generation
+        match marked {
+            true => automaton.advance_marked(&mut updated), 
+            false => automaton.advance(), 
+        };
     }
-    Ok(())
+    if let Some(stats) = &mut stats {
+        stats.finish()?;
+    }
+    sink.finish()?;
+    Ok(Report {
+        generations: written, 
+        cycle: detector.cycle(), 
+    })
 }
 
 // `main_result` is used to pretty-print the error returned from main
 fn main() -> MainResult {
     // read arguments from CLI
-    let (settings, initial) = {
-        let terminal_size = crossterm::terminal::size()?;
-        let args = Cli::parse();
-        let rule = Rule(args.rule);
-        let initial = args.initial.unwrap_or_else(|| {
-            let (width, _) = terminal_size;
-            Cells::new_random(width / 2) // div by 2 since each cell is 2 chars wide
-        });
+    let cli = Cli::parse();
+    match (cli.command, cli.run) {
+        (Some(Commands::Info(info)), _) => info.run(), 
+        (Some(Commands::Atlas(atlas)), _) => atlas.run()?, 
+        (Some(Commands::Basins(basins)), _) => basins.run()?, 
+        (Some(Commands::Classify(classify)), _) => classify.run()?, 
+        (Some(Commands::Damage(damage)), _) => damage.run()?, 
+        (Some(Commands::Preimages(preimages)), _) => preimages.run()?, 
+        (Some(Commands::Reverse(reverse)), _) => reverse.run()?, 
+        (None, Some(args)) => explore(args)?, 
+        (None, None) => unreachable!("clap requires either a command or the run arguments"), 
+    }
+    Ok(())
+}
+
+/// Runs the ECA with the given arguments. 
+fn explore(mut args: RunArgs) -> MainResult {
+    let plain = args.plain || !io::stdout().is_terminal();
+    let pattern = args.initial.take().unwrap_or(Pattern::Random(args.density));
+    // the seed is needed to replay random configurations and random updates
+    let seeded = matches!(pattern, Pattern::Random(_))
+        || args.noise.is_some()
+        || args.prob_table.is_some()
+        || args.update.is_random();
+    let density = match pattern {
+        Pattern::Random(density) => density, 
+        _ => args.density, 
+    };
+    let mut random = Random::new(args.seed.unwrap_or_else(rand::random), density, args.states);
+    let (settings, previous, initial) = {
+        // only consult the terminal size if it's actually needed
+        let terminal_size = || crossterm::terminal::size()
+            .ok()
+            .filter(|&(width, height)| width > 0 && height > 0)
+            .ok_or("Not running in a terminal; specify `--width` and `--generations` explicitly");
+        let rule = LocalRule::new(args.family, args.states, args.radius, args.rule)?;
+        let width = match (args.width, pattern.natural_width()) {
+            (Some(width), _) => Some(width), 
+            (None, Some(_)) => None, 
+            (None, None) => {
+                let (width, _) = terminal_size()?;
+                match plain {
+                    true => Some(width), 
+                    false => Some(width / 2), // div by 2 since each cell is 2 chars wide
+                }
+            }
+        };
+        let initial = pattern.build_states(width, args.states, &mut random.rng)?;
         let edge_handling = args.edges;
-        let generations = args.generations.unwrap_or_else(|| {
-            let (_, height) = terminal_size;
-            height
-        });
+        let generations = match args.generations {
+            Some(generations) => generations, 
+            None => {
+                let (_, height) = terminal_size()?;
+                height
+            }
+        };
         let delay = Duration::from_millis(args.delay.unwrap_or(0));
-        let settings = Settings {
-            rule, 
-            edge_handling, 
-            generations, 
-            delay, 
+        if args.prob_table.is_some() && rule.elementary().is_none() {
+            return Err("Probability tables only replace rules with two states and radius 1".into())
+        }
+        let hybrid = match (args.hybrid.take(), &args.hybrid_file) {
+            (Some(hybrid), _) => Some(hybrid), 
+            (None, Some(path)) => Some(fs::read_to_string(path)?.parse::<Hybrid>()?), 
+            (None, None) => None, 
         };
-        (settings, initial)
+        if hybrid.is_some() && rule.elementary().is_none() {
+            return Err("Hybrid automata only combine rules with two states and radius 1".into())
+        }
+        if args.second_order && args.update != Update::Synchronous {
+            return Err("Only synchronous updates are supported for second-order rules".into())
+        }
+        let stochastic = match (args.noise, args.prob_table) {
+            (None, None) => None, 
+            // a generator of its own, so that the randomness doesn't follow the random initial configuration
+            (noise, table) => Some(Stochastic::new(noise.unwrap_or(0.0), table, random.rng.gen())), 
+        };
+        if args.stop_on_cycle && (stochastic.is_some() || args.update.is_random()) {
+            return Err(concat!(
+                "Cycles aren't detected in runs with noise, a probability table or random updates, so they ", 
+                "can't stop on one", 
+            ).into())
+        }
+        // the cells updated at random are chosen with a generator of their own too
+        let update_seed = match args.update.is_random() {
+            true => random.rng.gen(), 
+            false => 0, 
+        };
+        let mut settings = Settings::new(rule, edge_handling).with_update(args.update, update_seed);
+        settings.generations = generations;
+        settings.delay = delay;
+        settings.second_order = args.second_order;
+        settings.stochastic = stochastic;
+        settings.hybrid = hybrid;
+        let previous = match &args.previous {
+            Some(previous) => {
+                let width = u16::try_from(initial.0.len()).map_err(|_| "Initial configuration is too wide")?;
+                previous.build_states(Some(width), args.states, &mut random.rng)?
+            }
+            None => Cells(vec![0; initial.0.len()]), 
+        };
+        (settings, previous, initial)
+    };
+    let delay = settings.delay;
+    let rule = settings.rule;
+    let stochastic = settings.stochastic.is_some() || settings.update.is_random();
+    let regions = match (args.boundaries, &settings.hybrid) {
+        (true, Some(hybrid)) => hybrid.regions(initial.0.len()), 
+        _ => Vec::new(), 
     };
+    let automaton = Automaton::with_previous(previous, initial, settings);
 
-    // setup terminal env
-    crossterm::terminal::enable_raw_mode()?;
-    crossterm::execute!{
-        io::stdout(), 
-        EnterAlternateScreen, 
-        Hide, 
-    }?;
-
-    // run all generations and make sure we reset terminal before any error is printed
-    let result = run(initial, settings);
-
-    // reset terminal env
-    crossterm::execute!{
-        io::stdout(), 
-        LeaveAlternateScreen, 
-        Show, 
-    }?;
-    crossterm::terminal::disable_raw_mode()?;
-
-    result.map_err(Into::into)
+    let colours = [args.dead_colour, args.alive_colour];
+    let format = match args.format {
+        Some(format) => Some(format), 
+        None => args.output.as_deref().map(Format::from_path).transpose()?, 
+    };
+    let sink: Option<Box<dyn Sink>> = match (format, args.gif, args.svg) {
+        // write straight to the file (or stdout); the terminal is left untouched
+        (Some(format), _, _) => {
+            let writer: Box<dyn Write> = match args.output {
+                Some(path) => Box::new(BufWriter::new(File::create(path)?)), 
+                None => Box::new(BufWriter::new(io::stdout())), 
+            };
+            match format {
+                Format::Png => {
+                    let tints = [args.updated_colour, args.boundary_colour];
+                    let image = Image::new(writer, args.cell_size.into(), colours, tints);
+                    Some(Box::new(image))
+                }
+                Format::Pbm => Some(Box::new(Bitmap::new(writer, false))), 
+                Format::PbmPlain => Some(Box::new(Bitmap::new(writer, true))), 
+                Format::Pgm => Some(Box::new(Graymap::new(writer))), 
+            }
+        }
+        (_, Some(path), _) => Some(Box::new(Gif::new(path, args.cell_size, colours, args.window, delay))), 
+        (_, _, Some(path)) => {
+            let caption = args.caption.then(|| {
+                let mut caption = match &automaton.settings().hybrid {
+                    Some(hybrid) => hybrid.to_string(), 
+                    None => rule.to_string(), 
+                };
+                caption[..1].make_ascii_uppercase();
+                caption
+            });
+            let style = svg::Style {
+                cell_size: args.cell_size, 
+                colours, 
+                grid: args.grid, 
+                caption, 
+            };
+            Some(Box::new(Svg::new(path, style)))
+        }
+        // print line by line, so that the output can be piped or redirected
+        _ if plain => Some(Box::new(Plain::new(args.symbols))), 
+        _ => None, 
+    };
+    let stats = match &args.stats {
+        Some(path) => Some(StatsFile::create(path, args.entropy_blocks.into())?), 
+        None => None, 
+    };
+    // cycle detection remembers every generation, so it's only done when something shows the cycle, and
+    // not at all when a generation repeating under noise or random updates doesn't mean the rest repeat
+    let detect = !stochastic && (args.report || args.stop_on_cycle || sink.is_none());
+    let result = match sink {
+        Some(mut sink) => {
+            sink.set_regions(&regions);
+            run(automaton, &mut random, detect, args.stop_on_cycle, stats, sink.as_mut())
+        }
+        None => {
+            // run all generations and make sure we reset terminal before any error is printed
+            let mut terminal = Terminal::enter()?;
+            terminal.set_regions(&regions);
+            let result = run(automaton, &mut random, detect, args.stop_on_cycle, stats, &mut terminal);
+            terminal.leave()?;
+            result
+        }
+    };
+    // the status line is gone by now, so make sure the seed isn't lost
+    if seeded {
+        eprintln!("Seed: {}", random.seed);
+    }
+    let report = result?;
+    if args.report {
+        eprintln!("Generations: {}", report.generations);
+        match (stochastic, report.cycle) {
+            (true, _) => eprintln!("Cycle: not detected with noise, a probability table or random updates"), 
+            (false, Some(cycle)) => eprintln!("Cycle: {cycle}"), 
+            (false, None) => eprintln!("Cycle: none found"), 
+        }
+    }
+    Ok(())
 }
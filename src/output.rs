@@ -0,0 +1,203 @@
+mod animation;
+mod image;
+mod netpbm;
+mod plain;
+mod stats;
+pub mod svg;
+mod terminal;
+
+use std::{
+    fmt::{self, Display, Formatter}, 
+    io, 
+    path::Path, 
+    str::FromStr, 
+    time::Duration, 
+};
+use clap::ValueEnum;
+use eca_explorer::{Cells, Cycle, LocalRule};
+
+pub use animation::Gif;
+pub use image::Image;
+pub use netpbm::{Bitmap, Graymap};
+pub use plain::{Plain, Symbols};
+pub use stats::StatsFile;
+pub use svg::Svg;
+pub use terminal::Terminal;
+
+/// Destination of the generations computed by `run`. 
+pub trait Sink {
+    /// Outputs a single generation. 
+    fn write(&mut self, cells: &Cells) -> io::Result<()>;
+
+    /// Outputs a single generation computed by an update scheme other than synchronous, marking the cells
+    /// that were updated. Written like any other generation by default. 
+    fn write_marked(&mut self, cells: &Cells, _updated: &[bool]) -> io::Result<()> {
+        self.write(cells)
+    }
+
+    /// Sets which cells lie in odd-numbered regions of neighbouring cells with the same rule in a hybrid
+    /// automaton, to show the rule boundaries in a second colour. Ignored by default. 
+    fn set_regions(&mut self, _odd: &[bool]) {}
+
+    /// Waits for a command from the user for at most `timeout`, or indefinitely if `None`. By default, 
+    /// there's no user input and nothing to wait for, so `None` is returned at once. 
+    fn command(&mut self, _timeout: Option<Duration>) -> io::Result<Option<Command>> {
+        Ok(None)
+    }
+
+    /// Marks that the rule changed to `rule` before the next generation. Ignored by default. 
+    fn divider(&mut self, _rule: LocalRule) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Displays the status of the run. Ignored by default. 
+    fn status(&mut self, _status: &Status) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Called once after the last generation has been written. 
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// A command issued by the user while running. 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// End the run prematurely. 
+    Quit, 
+    /// Pause or resume the run. 
+    Pause, 
+    /// Advance a single generation while paused. 
+    Step, 
+    /// Increase the delay between generations. 
+    Slower, 
+    /// Decrease the delay between generations. 
+    Faster, 
+    /// Restart from a new random configuration. 
+    Restart, 
+    /// Change to the rule with the next code. 
+    NextRule, 
+    /// Change to the rule with the previous code. 
+    PreviousRule, 
+    /// Flip the bit of the code with the given index (0-7). 
+    FlipBit(u8), 
+    /// Change to the rule with the given code, keeping its family and radius. 
+    SetCode(u64), 
+}
+
+/// State of the run shown to the user. 
+pub struct Status {
+    /// The rule, or the rules of a hybrid automaton. 
+    pub rule: String, 
+    pub generation: u64, 
+    pub delay: Duration, 
+    /// Seed of the random number generator. 
+    pub seed: u64, 
+    pub paused: bool, 
+    /// The cycle the run has entered, if one has been found. 
+    pub cycle: Option<Cycle>, 
+}
+
+/// File format of the spacetime diagram. 
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// PNG image. 
+    Png, 
+    /// Binary (P4) PBM bitmap with one pixel per cell. 
+    Pbm, 
+    /// ASCII (P1) PBM bitmap with one pixel per cell. 
+    PbmPlain, 
+    /// Binary (P5) PGM graymap with one pixel per cell, shaded by how long each cell has been alive. 
+    Pgm, 
+}
+
+impl Format {
+    /// Guesses the format from the extension of `path`. 
+    pub fn from_path(path: &Path) -> Result<Format, &'static str> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("png") => Ok(Format::Png), 
+            Some("pbm") => Ok(Format::Pbm), 
+            Some("pgm") => Ok(Format::Pgm), 
+            _ => Err("Unknown file extension; give the format with `--format`, or use `--svg` or `--gif`"), 
+        }
+    }
+}
+
+/// An RGB colour, parsed from a hex string such as `#ff8800`. 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Colour(pub [u8; 3]);
+
+/// Colours of the states above 1, for rules with more than two states. 
+pub const PALETTE: [Colour; 8] = [
+    Colour([0xe4, 0x1a, 0x1c]), 
+    Colour([0x37, 0x7e, 0xb8]), 
+    Colour([0x4d, 0xaf, 0x4a]), 
+    Colour([0x98, 0x4e, 0xa3]), 
+    Colour([0xff, 0x7f, 0x00]), 
+    Colour([0xff, 0xff, 0x33]), 
+    Colour([0xa6, 0x56, 0x28]), 
+    Colour([0xf7, 0x81, 0xbf]), 
+];
+
+impl Colour {
+    /// The colour of a cell in `state`, given the colours of `[dead, alive]` cells for states 0 and 1 and
+    /// taking higher states from the [`PALETTE`]. 
+    pub fn of_state(colours: [Colour; 2], state: u8) -> Colour {
+        match state {
+            0 | 1 => colours[state as usize], 
+            state => PALETTE[(state as usize - 2) % PALETTE.len()], 
+        }
+    }
+}
+
+impl Display for Colour {
+    /// Formats the colour as a hex string, as used by SVG and HTML. 
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Colour([r, g, b]) = self;
+        write!(f, "#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+impl FromStr for Colour {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<Colour, &'static str> {
+        let hex = string.strip_prefix('#').unwrap_or(string);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err("Colour must be given as 6 hex digits, e.g. `#ff8800`")
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16)
+            .map_err(|_| "Colour must only contain hex digits");
+        Ok(Colour([channel(0)?, channel(2)?, channel(4)?]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colours_parse_from_hex() {
+        assert_eq!("#ff8800".parse::<Colour>().unwrap(), Colour([0xff, 0x88, 0x00]));
+        assert_eq!("0a0B0c".parse::<Colour>().unwrap(), Colour([0x0a, 0x0b, 0x0c]));
+        assert!("#ff88".parse::<Colour>().is_err());
+        assert!("#ff88000".parse::<Colour>().is_err());
+        assert!("#gg0000".parse::<Colour>().is_err());
+        assert!("#ffé00".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn formats_are_guessed_from_extensions() {
+        assert_eq!(Format::from_path(Path::new("rule_30.png")), Ok(Format::Png));
+        assert_eq!(Format::from_path(Path::new("out/rule_30.pbm")), Ok(Format::Pbm));
+        assert_eq!(Format::from_path(Path::new("rule_30.pgm")), Ok(Format::Pgm));
+        assert!(Format::from_path(Path::new("rule_30.svg")).is_err());
+        assert!(Format::from_path(Path::new("rule_30.gif")).is_err());
+        assert!(Format::from_path(Path::new("rule_30")).is_err());
+    }
+
+    #[test]
+    fn colours_format_as_hex() {
+        assert_eq!(Colour([0xff, 0x88, 0x00]).to_string(), "#ff8800");
+        assert_eq!("#0a0b0c".parse::<Colour>().unwrap().to_string(), "#0a0b0c");
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::VecDeque, 
+    fs::File, 
+    io::{self, BufWriter}, 
+    iter, 
+    path::PathBuf, 
+    time::Duration, 
+};
+use gif::{Encoder, Frame, Repeat};
+use eca_explorer::Cells;
+use super::{Colour, Command, Sink, PALETTE};
+
+/// Renders the evolution as an animated GIF, where each frame shows a fixed-height window of the most recent
+/// generations, scrolling like in the terminal. 
+pub struct Gif {
+    path: PathBuf, 
+    cell_size: u16, 
+    colours: [Colour; 2], 
+    /// Number of generations in each frame; defaults to the width of the configuration. 
+    window: Option<u16>, 
+    /// Delay between frames, in hundredths of a second. 
+    delay: u16, 
+    /// The most recent generations, up to `window` of them. 
+    rows: VecDeque<Vec<u8>>, 
+    /// Created once the width of the frames is known. 
+    encoder: Option<Encoder<BufWriter<File>>>, 
+}
+
+impl Gif {
+    /// Creates a sink writing the animation to `path`. `colours` is given as `[dead, alive]`. 
+    pub fn new(path: PathBuf, cell_size: u16, colours: [Colour; 2], window: Option<u16>, delay: Duration)
+        -> Gif
+    {
+        Gif {
+            path, 
+            cell_size, 
+            colours, 
+            window, 
+            delay: (delay.as_millis() / 10).try_into().unwrap_or(u16::MAX), 
+            rows: VecDeque::new(), 
+            encoder: None, 
+        }
+    }
+
+    /// Frame dimensions in pixels. 
+    fn frame_size(&self, width: usize, window: u16) -> io::Result<(u16, u16)> {
+        let pixels = |cells: usize| u16::try_from(cells * self.cell_size as usize)
+            .map_err(|_| io::Error::other("GIF frames cannot be wider or taller than 65535 pixels"));
+        Ok((pixels(width)?, pixels(window.into())?))
+    }
+}
+
+impl Sink for Gif {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        let width = cells.0.len();
+        let window = self.window.unwrap_or(width.try_into().unwrap_or(u16::MAX));
+        let (frame_width, frame_height) = self.frame_size(width, window)?;
+
+        if self.encoder.is_none() {
+            // the pixels are the states themselves, indexing the colours of higher states after the others
+            let palette: Vec<u8> = self.colours
+                .iter()
+                .chain(&PALETTE)
+                .flat_map(|Colour(rgb)| rgb)
+                .copied()
+                .collect();
+            let file = BufWriter::new(File::create(&self.path)?);
+            let mut encoder = Encoder::new(file, frame_width, frame_height, &palette)
+                .map_err(io::Error::other)?;
+            encoder.set_repeat(Repeat::Infinite).map_err(io::Error::other)?;
+            self.encoder = Some(encoder);
+        }
+
+        if self.rows.len() == window as usize {
+            self.rows.pop_front();
+        }
+        self.rows.push_back(cells.0.clone());
+
+        // rows below the most recent generation are dead until the window is filled
+        let cell_size = self.cell_size as usize;
+        let dead = vec![0; width];
+        let pixels: Vec<u8> = self.rows
+            .iter()
+            .chain(iter::repeat(&dead))
+            .take(window as usize)
+            .flat_map(|row| {
+                let line: Vec<u8> = row.iter()
+                    .flat_map(|&cell| iter::repeat_n(cell, cell_size))
+                    .collect();
+                iter::repeat_n(line, cell_size).flatten()
+            })
+            .collect();
+        let mut frame = Frame::from_indexed_pixels(frame_width, frame_height, pixels, None);
+        frame.delay = self.delay;
+        self.encoder
+            .as_mut()
+            .expect("Encoder was created above")
+            .write_frame(&frame)
+            .map_err(io::Error::other)
+    }
+
+    fn command(&mut self, _timeout: Option<Duration>) -> io::Result<Option<Command>> {
+        // the delay is encoded in the frames instead
+        Ok(None)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        // the trailer is written when the encoder is consumed
+        match self.encoder.take() {
+            Some(encoder) => encoder.into_inner().map(drop), 
+            None => Ok(()), 
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    io::{self, Write}, 
+    iter, 
+};
+use eca_explorer::Cells;
+use super::{Colour, Sink};
+
+/// Renders the spacetime diagram as a PNG image, where each generation is a row of square cells. 
+pub struct Image {
+    writer: Box<dyn Write>, 
+    cell_size: u32, 
+    colours: [Colour; 2], 
+    /// Colours that cells updated by an update scheme other than synchronous, and cells in odd-numbered
+    /// regions of a hybrid rule are tinted with, in that order. 
+    tints: [Colour; 2], 
+    /// Whether each cell lies in an odd-numbered region of a hybrid rule; empty if they aren't shown. 
+    regions: Vec<bool>, 
+    /// Width of the image in pixels; set by the first generation written. 
+    width: u32, 
+    /// RGB pixel data for all generations written so far. 
+    pixels: Vec<u8>, 
+}
+
+impl Image {
+    /// Creates a sink that writes the image to `writer` once the run is finished. `colours` is given as
+    /// `[dead, alive]`. 
+    pub fn new(
+        writer: Box<dyn Write>, 
+        cell_size: u32, 
+        colours: [Colour; 2], 
+        tints: [Colour; 2], 
+    ) -> Image {
+        Image {
+            writer, 
+            cell_size, 
+            colours, 
+            tints, 
+            regions: Vec::new(), 
+            width: 0, 
+            pixels: Vec::new(), 
+        }
+    }
+
+    /// Appends the pixels of a generation, tinting the cells marked in `updated`. 
+    fn write_row(&mut self, cells: &Cells, updated: &[bool]) {
+        self.width = cells.0.len() as u32 * self.cell_size;
+        let row: Vec<u8> = cells.0.iter()
+            .enumerate()
+            .flat_map(|(i, &cell)| {
+                let Colour(mut rgb) = Colour::of_state(self.colours, cell);
+                let tint = match (updated.get(i), self.regions.get(i)) {
+                    (Some(&true), _) => Some(self.tints[0]), 
+                    (_, Some(&true)) => Some(self.tints[1]), 
+                    _ => None, 
+                };
+                if let Some(Colour(tint)) = tint {
+                    // halfway between the colour of the cell and the tint
+                    rgb = std::array::from_fn(|i| ((u16::from(rgb[i]) + u16::from(tint[i])) / 2) as u8);
+                }
+                iter::repeat_n(rgb, self.cell_size as usize)
+            })
+            .flatten()
+            .collect();
+        for _ in 0..self.cell_size {
+            self.pixels.extend_from_slice(&row);
+        }
+    }
+}
+
+impl Sink for Image {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        self.write_row(cells, &[]);
+        Ok(())
+    }
+
+    fn write_marked(&mut self, cells: &Cells, updated: &[bool]) -> io::Result<()> {
+        self.write_row(cells, updated);
+        Ok(())
+    }
+
+    fn set_regions(&mut self, odd: &[bool]) {
+        self.regions = odd.to_vec();
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let height = match self.width {
+            0 => 0, 
+            width => self.pixels.len() as u32 / 3 / width, 
+        };
+        let mut encoder = png::Encoder::new(&mut self.writer, self.width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| {
+                writer.write_image_data(&self.pixels)?;
+                writer.finish()
+            })
+            .map_err(io::Error::other)?;
+        self.writer.flush()
+    }
+}
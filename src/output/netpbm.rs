@@ -0,0 +1,177 @@
+use std::io::{self, Write};
+use eca_explorer::Cells;
+use super::Sink;
+
+/// Writes the spacetime diagram as a PBM bitmap with one pixel per cell, where cells in any live state are
+/// black. 
+pub struct Bitmap {
+    writer: Box<dyn Write>, 
+    /// Whether the ASCII (P1) variant is written instead of the binary (P4) one. 
+    plain: bool, 
+    rows: Vec<Vec<bool>>, 
+}
+
+impl Bitmap {
+    pub fn new(writer: Box<dyn Write>, plain: bool) -> Bitmap {
+        Bitmap {
+            writer, 
+            plain, 
+            rows: Vec::new(), 
+        }
+    }
+}
+
+impl Sink for Bitmap {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        self.rows.push(cells.0.iter().map(|&cell| cell != 0).collect());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let width = self.rows.first().map_or(0, Vec::len);
+        let height = self.rows.len();
+        let writer = &mut self.writer;
+
+        match self.plain {
+            true => {
+                writeln!(writer, "P1\n{width} {height}")?;
+                for row in &self.rows {
+                    let line: Vec<&str> = row.iter()
+                        .map(|&cell| if cell { "1" } else { "0" })
+                        .collect();
+                    writeln!(writer, "{}", line.join(" "))?;
+                }
+            }
+            false => {
+                writeln!(writer, "P4\n{width} {height}")?;
+                for row in &self.rows {
+                    // each row is padded to a whole number of bytes, most significant bit first
+                    let bytes: Vec<u8> = row
+                        .chunks(8)
+                        .map(|chunk| chunk.iter()
+                            .enumerate()
+                            .fold(0, |byte, (i, &cell)| byte | u8::from(cell) << (7 - i))
+                        )
+                        .collect();
+                    writer.write_all(&bytes)?;
+                }
+            }
+        }
+        writer.flush()
+    }
+}
+
+/// Writes the spacetime diagram as a PGM graymap with one pixel per cell, where the shade of each cell is its
+/// age: the number of consecutive generations it has been alive for. Dead cells are black, and the oldest
+/// cells are white. 
+pub struct Graymap {
+    writer: Box<dyn Write>, 
+    /// Ages of the cells in the last generation written. 
+    ages: Vec<u16>, 
+    rows: Vec<Vec<u16>>, 
+}
+
+impl Graymap {
+    pub fn new(writer: Box<dyn Write>) -> Graymap {
+        Graymap {
+            writer, 
+            ages: Vec::new(), 
+            rows: Vec::new(), 
+        }
+    }
+}
+
+impl Sink for Graymap {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        // the configuration might have been restarted with a different width
+        self.ages.resize(cells.0.len(), 0);
+        for (age, &cell) in self.ages.iter_mut().zip(&cells.0) {
+            *age = match cell {
+                0 => 0, 
+                _ => age.saturating_add(1), 
+            };
+        }
+        self.rows.push(self.ages.clone());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let width = self.rows.first().map_or(0, Vec::len);
+        let height = self.rows.len();
+        let max_age = self.rows.iter().flatten().copied().max().unwrap_or(0).max(1);
+        let writer = &mut self.writer;
+
+        writeln!(writer, "P5\n{width} {height}\n{max_age}")?;
+        for row in &self.rows {
+            // samples take up two bytes (most significant first) if they don't fit in one
+            let bytes: Vec<u8> = match max_age {
+                0..=255 => row.iter().map(|&age| age as u8).collect(), 
+                _ => row.iter().flat_map(|age| age.to_be_bytes()).collect(), 
+            };
+            writer.write_all(&bytes)?;
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+    use super::*;
+
+    /// A writer whose contents can still be read once it has been boxed into a sink. 
+    #[derive(Clone, Default)]
+    struct Buffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Writes `rows` to the sink created by `new` and returns the finished file. 
+    fn render<S: Sink>(new: impl FnOnce(Box<dyn Write>) -> S, rows: &[&str]) -> Vec<u8> {
+        let buffer = Buffer::default();
+        let mut sink = new(Box::new(buffer.clone()));
+        for row in rows {
+            sink.write(&row.parse().unwrap()).unwrap();
+        }
+        sink.finish().unwrap();
+        buffer.0.take()
+    }
+
+    #[test]
+    fn ascii_bitmap() {
+        let pbm = render(|writer| Bitmap::new(writer, true), &["101", "020"]);
+        assert_eq!(pbm, b"P1\n3 2\n1 0 1\n0 1 0\n");
+    }
+
+    #[test]
+    fn binary_bitmap_packs_rows_most_significant_bit_first() {
+        let pbm = render(|writer| Bitmap::new(writer, false), &["000010000", "100000001"]);
+        // each row is padded to whole bytes
+        assert_eq!(pbm, [b"P4\n9 2\n".as_slice(), &[0x08, 0x00], &[0x80, 0x80]].concat());
+    }
+
+    #[test]
+    fn graymap_counts_ages_in_single_bytes() {
+        let pgm = render(Graymap::new, &["110", "011"]);
+        assert_eq!(pgm, [b"P5\n3 2\n2\n".as_slice(), &[1, 1, 0], &[0, 2, 1]].concat());
+    }
+
+    #[test]
+    fn graymap_uses_two_bytes_for_ages_above_255() {
+        let rows = vec!["101"; 300];
+        let pgm = render(Graymap::new, &rows);
+        let header = b"P5\n3 300\n300\n";
+        assert_eq!(&pgm[..header.len()], header);
+        let samples = &pgm[header.len()..];
+        assert_eq!(samples.len(), 300 * 3 * 2);
+        assert_eq!(&samples[..6], &[0, 1, 0, 0, 0, 1]);
+        assert_eq!(&samples[samples.len() - 6..], &[0x01, 0x2c, 0, 0, 0x01, 0x2c]);
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    io::{self, BufWriter, Stdout, Write}, 
+    str::FromStr, 
+    time::Duration, 
+};
+use eca_explorer::Cells;
+use super::{Command, Sink};
+
+/// Prints each generation as a plain line of characters to stdout, without touching the terminal mode. 
+pub struct Plain {
+    symbols: Symbols, 
+    stdout: BufWriter<Stdout>, 
+    /// Set when the reading end of stdout has been closed (e.g. when piped into `head`). 
+    closed: bool, 
+}
+
+impl Plain {
+    pub fn new(symbols: Symbols) -> Plain {
+        Plain {
+            symbols, 
+            stdout: BufWriter::new(io::stdout()), 
+            closed: false, 
+        }
+    }
+}
+
+impl Sink for Plain {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        let Symbols([dead, alive]) = self.symbols;
+        let line: String = cells.0.iter()
+            .map(|cell| match cell {
+                0 => dead, 
+                1 => alive, 
+                // higher states are printed as their digit
+                state => char::from(b'0' + state), 
+            })
+            .chain(['\n'])
+            .collect();
+        match self.stdout.write_all(line.as_bytes()) {
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(())
+            }
+            result => result, 
+        }
+    }
+
+    fn command(&mut self, timeout: Option<Duration>) -> io::Result<Option<Command>> {
+        if self.closed {
+            return Ok(Some(Command::Quit))
+        }
+        // flush before delaying so that each generation shows up on time
+        let delay = timeout.unwrap_or_default();
+        if !delay.is_zero() {
+            match self.stdout.flush() {
+                Err(error) if error.kind() == io::ErrorKind::BrokenPipe => {
+                    self.closed = true;
+                    return Ok(Some(Command::Quit))
+                }
+                result => result?, 
+            }
+            std::thread::sleep(delay);
+        }
+        Ok(None)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        match self.stdout.flush() {
+            Err(error) if error.kind() == io::ErrorKind::BrokenPipe => Ok(()), 
+            result => result, 
+        }
+    }
+}
+
+/// The two characters used to print dead and alive cells, in that order. Cells in states above 1 are
+/// printed as their digit. 
+#[derive(Clone, Copy, Debug)]
+pub struct Symbols(pub [char; 2]);
+
+impl FromStr for Symbols {
+    type Err = &'static str;
+
+    /// Parses a string of exactly two characters, such as `.#`. 
+    fn from_str(string: &str) -> Result<Symbols, &'static str> {
+        let mut chars = string.chars();
+        match [chars.next(), chars.next(), chars.next()] {
+            [Some(dead), Some(alive), None] => Ok(Symbols([dead, alive])), 
+            _ => Err("Symbols must be exactly two characters: one for dead cells and one for alive cells"), 
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symbols_are_exactly_two_characters() {
+        assert_eq!(".#".parse::<Symbols>().unwrap().0, ['.', '#']);
+        assert_eq!("░█".parse::<Symbols>().unwrap().0, ['░', '█']);
+        assert!(".".parse::<Symbols>().is_err());
+        assert!(".#!".parse::<Symbols>().is_err());
+        assert!("".parse::<Symbols>().is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use std::{
+    fs::File, 
+    io::{self, BufWriter, Write}, 
+    path::Path, 
+};
+use eca_explorer::{Cells, Statistics};
+
+/// Writes the [`Statistics`] of each generation as a row of a CSV file, alongside the diagram. 
+pub struct StatsFile {
+    writer: BufWriter<File>, 
+    /// Largest block size to compute the block entropy for. 
+    max_block_size: usize, 
+    /// The generation recorded last, to compute the Hamming distance from. 
+    previous: Option<Cells>, 
+}
+
+impl StatsFile {
+    /// Creates the file and writes the header. 
+    pub fn create(path: &Path, max_block_size: usize) -> io::Result<StatsFile> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let entropies: String = (1..=max_block_size)
+            .map(|size| format!("entropy_{size},"))
+            .collect();
+        writeln!(writer, "generation,rule,density,{entropies}runs,mean_run_length,hamming_distance")?;
+        Ok(StatsFile {
+            writer, 
+            max_block_size, 
+            previous: None, 
+        })
+    }
+
+    /// Writes the statistics of a generation computed by `rule`, the code of the rule or the label of a
+    /// hybrid automaton. 
+    pub fn record(&mut self, generation: u64, rule: &str, cells: &Cells) -> io::Result<()> {
+        let Statistics { density, entropies, runs, mean_run_length, hamming_distance } =
+            Statistics::new(cells, self.previous.as_ref(), self.max_block_size);
+        let entropies: String = entropies
+            .into_iter()
+            .map(|entropy| format!("{entropy:.6},"))
+            .collect();
+        // the first generation has no previous one to compare to
+        let hamming_distance = hamming_distance.map(|distance| distance.to_string()).unwrap_or_default();
+        // the rules of a hybrid automaton are separated by commas, which would split the column
+        let rule = match rule.contains(',') {
+            true => format!("\"{rule}\""), 
+            false => rule.to_string(), 
+        };
+        writeln!(
+            self.writer, 
+            "{generation},{rule},{density:.6},{entropies}{runs},{mean_run_length:.6},{hamming_distance}", 
+        )?;
+        match &mut self.previous {
+            Some(previous) => previous.clone_from(cells), 
+            None => self.previous = Some(cells.clone()), 
+        }
+        Ok(())
+    }
+
+    /// Forgets the previous generation, e.g. after restarting from a new configuration. 
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    fmt::Write as _, 
+    fs, 
+    io, 
+    path::PathBuf, 
+};
+use eca_explorer::Cells;
+use super::{Colour, Sink};
+
+/// Height of the caption below the diagram, in pixels. 
+const CAPTION_HEIGHT: usize = 24;
+
+/// Renders the spacetime diagram as an SVG image, with one rectangle per horizontal run of live cells in the
+/// same state. 
+pub struct Svg {
+    path: PathBuf, 
+    style: Style, 
+    rows: Vec<Vec<u8>>, 
+}
+
+impl Svg {
+    /// Creates a sink that writes the image to `path` once the run is finished. 
+    pub fn new(path: PathBuf, style: Style) -> Svg {
+        Svg {
+            path, 
+            style, 
+            rows: Vec::new(), 
+        }
+    }
+}
+
+/// How the SVG image is drawn. 
+#[derive(Clone, Debug)]
+pub struct Style {
+    /// Width and height in pixels of each cell. 
+    pub cell_size: u16, 
+    /// Colours of `[dead, alive]` cells. 
+    pub colours: [Colour; 2], 
+    /// Whether grid lines are drawn between the cells. 
+    pub grid: bool, 
+    /// Text shown below the diagram. 
+    pub caption: Option<String>, 
+}
+
+/// The SVG document of the spacetime diagram with the given generations. 
+pub fn document(rows: &[Vec<u8>], style: &Style) -> String {
+    let size = usize::from(style.cell_size);
+    let [dead, alive] = style.colours;
+    let width = rows.first().map_or(0, Vec::len) * size;
+    let height = rows.len() * size;
+    let total_height = height + style.caption.as_ref().map_or(0, |_| CAPTION_HEIGHT);
+
+    // writing to a string never fails
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg, 
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{total_height}" viewBox="0 0 {width} {total_height}">"#, 
+    );
+    let _ = writeln!(svg, r#"<rect width="{width}" height="{total_height}" fill="{dead}"/>"#);
+    let _ = writeln!(svg, r#"<g fill="{alive}" shape-rendering="crispEdges">"#);
+    for (y, row) in rows.iter().enumerate() {
+        for (start, length, state) in runs(row) {
+            // cells in higher states override the fill of the group
+            let fill = match state {
+                1 => String::new(), 
+                state => format!(r#" fill="{}""#, Colour::of_state(style.colours, state)), 
+            };
+            let _ = writeln!(
+                svg, 
+                r#"<rect x="{}" y="{}" width="{}" height="{size}"{fill}/>"#, 
+                start * size, 
+                y * size, 
+                length * size, 
+            );
+        }
+    }
+    svg += "</g>\n";
+    if style.grid {
+        let _ = writeln!(
+            svg, 
+            concat!(
+                r#"<defs><pattern id="grid" width="{size}" height="{size}" patternUnits="userSpaceOnUse">"#, 
+                r#"<path d="M {size} 0 L 0 0 0 {size}" fill="none" stroke="gray" stroke-width="0.5"/>"#, 
+                r#"</pattern></defs>"#, 
+            ), 
+            size = size, 
+        );
+        let _ = writeln!(svg, r#"<rect width="{width}" height="{height}" fill="url(#grid)"/>"#);
+    }
+    if let Some(caption) = &style.caption {
+        let _ = writeln!(
+            svg, 
+            r#"<text x="{}" y="{}" text-anchor="middle" font-family="sans-serif" font-size="14" fill="{alive}">{}</text>"#, 
+            width / 2, 
+            height + CAPTION_HEIGHT - 7, 
+            escape(caption), 
+        );
+    }
+    svg += "</svg>\n";
+    svg
+}
+
+impl Sink for Svg {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        self.rows.push(cells.0.clone());
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        fs::write(&self.path, document(&self.rows, &self.style))
+    }
+}
+
+/// The runs of consecutive alive cells in the same state in `row`, as `(start, length, state)`. 
+fn runs(row: &[u8]) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+    let mut x = 0;
+    std::iter::from_fn(move || {
+        let start = x + row[x..].iter().position(|&cell| cell != 0)?;
+        let state = row[start];
+        let length = row[start..].iter().take_while(|&&cell| cell == state).count();
+        x = start + length;
+        Some((start, length, state))
+    })
+}
+
+/// Escapes the characters that have special meaning in XML text. 
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::output::PALETTE;
+    use super::*;
+
+    #[test]
+    fn runs_merge_neighbouring_cells_in_the_same_state() {
+        assert_eq!(runs(&[0, 1, 1, 2, 0]).collect::<Vec<_>>(), vec![(1, 2, 1), (3, 1, 2)]);
+        assert_eq!(runs(&[1, 1, 0, 1]).collect::<Vec<_>>(), vec![(0, 2, 1), (3, 1, 1)]);
+        assert_eq!(runs(&[2, 2, 2]).collect::<Vec<_>>(), vec![(0, 3, 2)]);
+        assert_eq!(runs(&[0, 0, 0]).count(), 0);
+    }
+
+    #[test]
+    fn document_draws_one_rectangle_per_run() {
+        let style = Style {
+            cell_size: 2, 
+            colours: [Colour([255, 255, 255]), Colour([0, 0, 0])], 
+            grid: false, 
+            caption: Some("Rule <30>".to_owned()), 
+        };
+        let svg = document(&[vec![0, 1, 1, 2], vec![1, 0, 0, 0]], &style);
+        assert!(svg.contains(r#"width="8" height="28""#));
+        assert!(svg.contains(r#"<rect x="2" y="0" width="4" height="2"/>"#));
+        // cells in higher states override the fill of the group
+        let fill = PALETTE[0];
+        assert!(svg.contains(&format!(r#"<rect x="6" y="0" width="2" height="2" fill="{fill}"/>"#)));
+        assert!(svg.contains(r#"<rect x="0" y="2" width="2" height="2"/>"#));
+        assert!(svg.contains(">Rule &lt;30&gt;</text>"));
+    }
+}
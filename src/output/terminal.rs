@@ -0,0 +1,220 @@
+use std::{
+    io::{self, Write}, 
+    iter, 
+    time::{Duration, Instant}, 
+};
+use crossterm::{
+    cursor::{Hide, MoveTo, RestorePosition, SavePosition, Show}, 
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers}, 
+    style::{Color, Print, Stylize}, 
+    terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen}, 
+};
+use eca_explorer::{Cells, LocalRule};
+use super::{Command, Sink, Status, PALETTE};
+
+/// Prints each generation as a new line in an alternate terminal screen, with a status line at the top. 
+pub struct Terminal {
+    /// Number of cells in the last generation written. 
+    width: usize, 
+    /// The last status displayed, kept so that the status line can be redrawn while typing a rule. 
+    status: String, 
+    /// Digits of a rule number being typed, if any. 
+    entry: Option<String>, 
+    /// Whether each cell lies in an odd-numbered region of a hybrid rule; empty if they aren't shown. 
+    regions: Vec<bool>, 
+}
+
+impl Terminal {
+    /// Sets up the terminal environment. 
+    pub fn enter() -> io::Result<Terminal> {
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!{
+            io::stdout(), 
+            EnterAlternateScreen, 
+            Hide, 
+        }?;
+        Ok(Terminal {
+            width: 0, 
+            status: String::new(), 
+            entry: None, 
+            regions: Vec::new(), 
+        })
+    }
+
+    /// Resets the terminal environment. 
+    pub fn leave(self) -> io::Result<()> {
+        crossterm::execute!{
+            io::stdout(), 
+            LeaveAlternateScreen, 
+            Show, 
+        }?;
+        crossterm::terminal::disable_raw_mode()
+    }
+
+    /// Draws the status line over the top row, which is the oldest generation on screen. 
+    fn draw_status(&self) -> io::Result<()> {
+        let mut string = self.status.clone();
+        if let Some(entry) = &self.entry {
+            string += &format!(" new rule: {entry}_ ");
+        }
+        let mut stdout = io::stdout();
+        crossterm::queue!{
+            stdout, 
+            SavePosition, 
+            MoveTo(0, 0), 
+            Clear(ClearType::CurrentLine), 
+            Print(string.reverse()), 
+            RestorePosition, 
+        }?;
+        stdout.flush()
+    }
+
+    /// Maps key presses to commands. Unbound keys are ignored. 
+    fn key_command(&mut self, key: KeyEvent) -> io::Result<Option<Command>> {
+        if key.kind != KeyEventKind::Press {
+            return Ok(None)
+        }
+        // a rule number is being typed
+        if let Some(entry) = &mut self.entry {
+            match key.code {
+                KeyCode::Char(digit @ '0'..='9') if entry.len() < 19 => entry.push(digit), 
+                KeyCode::Backspace => {
+                    entry.pop();
+                }
+                KeyCode::Esc => self.entry = None, 
+                KeyCode::Enter => {
+                    let rule = entry.parse().ok().map(Command::SetCode);
+                    self.entry = None;
+                    self.draw_status()?;
+                    return Ok(rule)
+                }
+                _ => (), 
+            }
+            self.draw_status()?;
+            return Ok(None)
+        }
+        let command = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Command::Quit, 
+            KeyCode::Char('q') | KeyCode::Esc => Command::Quit, 
+            KeyCode::Char(' ') => Command::Pause, 
+            KeyCode::Char('n') => Command::Step, 
+            KeyCode::Char('+') => Command::Slower, 
+            KeyCode::Char('-') => Command::Faster, 
+            KeyCode::Char('r') => Command::Restart, 
+            KeyCode::Up => Command::NextRule, 
+            KeyCode::Down => Command::PreviousRule, 
+            KeyCode::Char(digit @ '1'..='8') => Command::FlipBit(digit as u8 - b'1'), 
+            KeyCode::Char(':') => {
+                self.entry = Some(String::new());
+                self.draw_status()?;
+                return Ok(None)
+            }
+            _ => return Ok(None), 
+        };
+        Ok(Some(command))
+    }
+}
+
+/// A single cell, 2 characters wide. Updated cells are drawn in yellow, or shaded for states with colours of
+/// their own, and cells in every other region of a hybrid rule are drawn in cyan. 
+fn glyph(state: u8, updated: bool, odd_region: bool) -> String {
+    let glyph = match (state, updated) {
+        (0, _) => "╶╴", 
+        (1, _) | (_, false) => "██", 
+        (_, true) => "▓▓", 
+    };
+    let colour = match (state, updated, odd_region) {
+        (0 | 1, true, _) => Color::Yellow, 
+        (0 | 1, false, true) => Color::Cyan, 
+        (0 | 1, false, false) => return glyph.to_owned(), 
+        (state, _, _) => {
+            let [r, g, b] = PALETTE[(state as usize - 2) % PALETTE.len()].0;
+            Color::Rgb { r, g, b }
+        }
+    };
+    glyph.with(colour).to_string()
+}
+
+impl Sink for Terminal {
+    fn write(&mut self, cells: &Cells) -> io::Result<()> {
+        self.write_marked(cells, &[])
+    }
+
+    fn write_marked(&mut self, cells: &Cells, updated: &[bool]) -> io::Result<()> {
+        self.width = cells.0.len();
+
+        let line = match (cells.max_state(), updated.is_empty() && self.regions.is_empty()) {
+            (0 | 1, true) => cells.to_string(), 
+            _ => cells.0
+                .iter()
+                .enumerate()
+                .map(|(i, &cell)| {
+                    glyph(cell, updated.get(i) == Some(&true), self.regions.get(i) == Some(&true))
+                })
+                .collect(), 
+        };
+        // explicit `\r` is needed in raw mode
+        crossterm::execute!{
+            io::stdout(), 
+            Print(format!("\n\r{line}")), 
+        }
+    }
+
+    fn set_regions(&mut self, odd: &[bool]) {
+        self.regions = odd.to_vec();
+    }
+
+    fn command(&mut self, timeout: Option<Duration>) -> io::Result<Option<Command>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            // this also delays
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if !crossterm::event::poll(remaining)? {
+                    return Ok(None)
+                }
+            }
+            if let Event::Key(key) = crossterm::event::read()? {
+                if let Some(command) = self.key_command(key)? {
+                    return Ok(Some(command))
+                }
+            }
+        }
+    }
+
+    fn divider(&mut self, rule: LocalRule) -> io::Result<()> {
+        let label = format!("╴{rule}╶");
+        let line: String = label.chars()
+            .chain(iter::repeat('─'))
+            .take(self.width * 2) // each cell is 2 chars wide
+            .collect();
+        crossterm::execute!{
+            io::stdout(), 
+            Print(format!("\n\r{}", line.dim())), 
+        }
+    }
+
+    fn status(&mut self, status: &Status) -> io::Result<()> {
+        let Status { rule, generation, delay, seed, paused, cycle } = status;
+        self.status = format!(
+            " {rule}  generation {generation}  delay {}ms  seed {seed} ", 
+            delay.as_millis(), 
+        );
+        if let Some(cycle) = cycle {
+            self.status += &format!(" cycle: {cycle} ");
+        }
+        if *paused {
+            self.status += " paused ";
+        }
+        self.draw_status()
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        // wait for user input before exiting
+        loop {
+            if let Event::Key(_) = crossterm::event::read()? {
+                break Ok(())
+            }
+        }
+    }
+}
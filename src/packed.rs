@@ -0,0 +1,171 @@
+use crate::{Cells, EdgeHandling, Rule, Settings, Storage, Update};
+
+/// Number of cells stored in each word. 
+const BITS: usize = u64::BITS as usize;
+
+/// Bit-packed cell configuration, storing 64 cells per `u64` word. The next generation is computed 64 cells
+/// at a time using a boolean formula derived from the rule, which makes it suitable for very wide and long
+/// runs. Rules with a radius above 1, [stochastic](crate::Stochastic) updates, non-synchronous
+/// [update schemes](crate::Update) and [hybrid](crate::Hybrid) rules are computed one cell at a time
+/// instead, and rules with more than two states aren't supported. 
+///
+/// Cell `i` is stored in bit `i % 64` of word `i / 64`; bits beyond the last cell are always zero. 
+///
+/// ```
+/// use eca_explorer::{step, Cells, EdgeHandling, PackedCells, Rule, Settings};
+///
+/// let settings = Settings::new(Rule(30), EdgeHandling::Wrap);
+/// let cells: Cells = "0001000".parse().unwrap();
+/// let packed = PackedCells::from(&cells);
+/// let (packed, _) = step(packed.clone(), packed, &settings);
+/// assert_eq!(Cells::from(&packed), "0011100".parse().unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PackedCells {
+    words: Vec<u64>, 
+    width: usize, 
+}
+
+impl PackedCells {
+    /// Number of cells. 
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The value of the cell at `index`. 
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.width, "Cell index out of bounds");
+        self.words[index / BITS] >> (index % BITS) & 1 != 0
+    }
+
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.width, "Cell index out of bounds");
+        let bit = 1 << (index % BITS);
+        match value {
+            true => self.words[index / BITS] |= bit, 
+            false => self.words[index / BITS] &= !bit, 
+        }
+    }
+
+    /// Mask of the bits in the last word that hold cells. 
+    fn last_mask(&self) -> u64 {
+        match self.width % BITS {
+            0 => u64::MAX, 
+            used => (1 << used) - 1, 
+        }
+    }
+}
+
+/// Cells in any live state are packed as `1`. 
+impl From<&Cells> for PackedCells {
+    fn from(cells: &Cells) -> PackedCells {
+        let words = cells.0
+            .chunks(BITS)
+            .map(|chunk| chunk.iter()
+                .enumerate()
+                .fold(0, |word, (i, &cell)| word | u64::from(cell != 0) << i)
+            )
+            .collect();
+        PackedCells {
+            words, 
+            width: cells.0.len(), 
+        }
+    }
+}
+
+impl From<&PackedCells> for Cells {
+    fn from(packed: &PackedCells) -> Cells {
+        Cells((0..packed.width).map(|i| u8::from(packed.get(i))).collect())
+    }
+}
+
+impl Storage for PackedCells {
+    fn step_into(&self, next: &mut PackedCells, settings: &Settings) {
+        assert_eq!(settings.rule.states(), 2, "packed cells only hold two states");
+        // random and non-synchronous updates are computed one cell at a time, in the same order as `Cells`, 
+        // and so are hybrid rules
+        let (Some(rule), None, Update::Synchronous, None) = (
+            settings.rule.elementary(), 
+            &settings.stochastic, 
+            settings.update, 
+            &settings.hybrid, 
+        ) else {
+            let cells = Cells::from(self);
+            let mut stepped = Cells::from(&*next);
+            cells.step_into(&mut stepped, settings);
+            *next = PackedCells::from(&stepped);
+            return
+        };
+        let words = &self.words;
+        let last = words.len() - 1;
+        let formula = Formula::new(rule);
+        // the edges of the previous generation, which second-order rules combine with the copied edges
+        let previous_edges = match settings.second_order {
+            true => [next.get(0), next.get(self.width - 1)], 
+            false => [false; 2], 
+        };
+
+        let stepped = (0..words.len()).map(|i| {
+            let centre = words[i];
+            // the neighbours of each cell, shifted into the cell's bit position. bits shifted in from beyond
+            // the edges are zero, matching `EdgeHandling::Crop`
+            let mut left = centre << 1 | if i > 0 { words[i - 1] >> (BITS - 1) } else { 0 };
+            let mut right = centre >> 1 | if i < last { words[i + 1] << (BITS - 1) } else { 0 };
+
+            if let EdgeHandling::Wrap = settings.edge_handling {
+                if i == 0 {
+                    left |= u64::from(self.get(self.width - 1));
+                }
+                if i == last {
+                    right |= u64::from(self.get(0)) << ((self.width - 1) % BITS);
+                }
+            }
+            formula.apply(left, centre, right)
+        });
+        match settings.second_order {
+            true => {
+                assert_eq!(next.width, self.width);
+                for (previous, word) in next.words.iter_mut().zip(stepped) {
+                    *previous ^= word;
+                }
+            }
+            false => {
+                next.width = self.width;
+                next.words.clear();
+                next.words.extend(stepped);
+            }
+        }
+        next.words[last] &= self.last_mask();
+
+        if let EdgeHandling::Copy = settings.edge_handling {
+            next.set(0, self.get(0) ^ previous_edges[0]);
+            next.set(self.width - 1, self.get(self.width - 1) ^ previous_edges[1]);
+        }
+    }
+}
+
+/// A rule as a disjunction of its minterms, i.e. the neighbourhoods which map to `1`. 
+struct Formula {
+    /// For each minterm, the masks selecting the left, centre and right neighbours, or their complements. 
+    minterms: Vec<[u64; 3]>, 
+}
+
+impl Formula {
+    fn new(rule: Rule) -> Formula {
+        let minterms = (0..8)
+            .filter(|n| rule.0 >> n & 1 != 0)
+            .map(|n| [n >> 2 & 1, n >> 1 & 1, n & 1].map(|bit| match bit {
+                0 => u64::MAX, 
+                _ => 0, 
+            }))
+            .collect();
+        Formula { minterms }
+    }
+
+    /// Applies the rule to 64 neighbourhoods at once. 
+    fn apply(&self, left: u64, centre: u64, right: u64) -> u64 {
+        self.minterms
+            .iter()
+            .fold(0, |word, [l, c, r]| word | (left ^ l) & (centre ^ c) & (right ^ r))
+    }
+}
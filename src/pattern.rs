@@ -0,0 +1,194 @@
+use std::{iter, str::FromStr};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rand::Rng;
+use crate::Cells;
+
+/// Widest configuration a pattern can build, as wide as a width can be given. 
+const MAX_WIDTH: usize = u16::MAX as usize;
+/// Error for patterns wider than [`MAX_WIDTH`]. 
+const TOO_WIDE: &str = "Initial configuration must be at most 65535 cells wide";
+
+/// A description of an initial cell configuration, which may depend on the width of the run. 
+///
+/// Patterns are parsed from the following syntax:
+///
+/// | Pattern          | Configuration                                                                    |
+/// |------------------|----------------------------------------------------------------------------------|
+/// | `0120`           | The given cells, one digit per state                                             |
+/// | `27*0,1,27*0`    | Run-length form; comma-separated runs, each optionally prefixed by a count       |
+/// | `hex:1f0`        | Hex digits, 4 cells each, most significant bit first (also `0x1f0`)             |
+/// | `base64:8A==`    | Base64-encoded bytes, 8 cells each, most significant bit first (also `b64:`)    |
+/// | `single`         | A single cell in state 1 in the centre                                           |
+/// | `center:101`     | The given cells (in any of the forms above) in the centre                        |
+/// | `repeat:0110`    | The given cells (in any of the forms above) repeated to fill the width           |
+/// | `random:0.3`     | Random cells, each alive with the given probability, in a random live state      |
+///
+/// When given a width, the first four forms are padded with dead cells on the right. 
+///
+/// ```
+/// use eca_explorer::{Cells, Pattern};
+///
+/// let mut rng = rand::thread_rng();
+/// let pattern: Pattern = "center:11".parse().unwrap();
+/// assert_eq!(pattern.build(Some(6), &mut rng), "001100".parse());
+///
+/// let pattern: Pattern = "2*0,1,3*01".parse().unwrap();
+/// assert_eq!(pattern.build(None, &mut rng), "001010101".parse());
+/// assert_eq!(pattern.build(Some(10), &mut rng), "0010101010".parse());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pattern {
+    /// The given cells, padded on the right. 
+    Literal(Vec<u8>), 
+    /// A single alive cell in the centre. 
+    Single, 
+    /// The given cells in the centre. 
+    Center(Vec<u8>), 
+    /// The given cells repeated to fill the width. 
+    Repeat(Vec<u8>), 
+    /// Random cells, each alive with the given probability. 
+    Random(f64), 
+}
+
+impl Pattern {
+    /// The width of the configuration if none is given, or `None` if the pattern needs a width. 
+    pub fn natural_width(&self) -> Option<usize> {
+        match self {
+            Pattern::Literal(cells) | Pattern::Center(cells) => Some(cells.len()), 
+            Pattern::Single | Pattern::Repeat(_) | Pattern::Random(_) => None, 
+        }
+    }
+
+    /// Builds a configuration of the given width, which must be specified if the pattern has no
+    /// [natural width](Pattern::natural_width). Random cells are drawn from `rng`. Fails for configurations
+    /// narrower than 3 cells. 
+    pub fn build(&self, width: Option<u16>, rng: &mut impl Rng) -> Result<Cells, &'static str> {
+        self.build_states(width, 2, rng)
+    }
+
+    /// Builds a configuration of the given width for a rule with the given number of states, drawing the
+    /// states of random cells from all of them. Fails if the pattern has cells in other states. 
+    ///
+    /// ```
+    /// use eca_explorer::Pattern;
+    ///
+    /// let mut rng = rand::thread_rng();
+    /// let pattern: Pattern = "0120".parse().unwrap();
+    /// assert!(pattern.build_states(None, 3, &mut rng).is_ok());
+    /// assert!(pattern.build(None, &mut rng).is_err());
+    /// assert!("65536*0".parse::<Pattern>().is_err());
+    /// assert!("1,32767*01".parse::<Pattern>().is_ok());
+    /// ```
+    pub fn build_states(&self, width: Option<u16>, states: u8, rng: &mut impl Rng)
+        -> Result<Cells, &'static str>
+    {
+        let width = match width.map(usize::from).or(self.natural_width()) {
+            Some(width) => width, 
+            None => return Err("Pattern requires a width"), 
+        };
+        if width < 3 {
+            return Err("Initial configuration must be at least 3 cells wide")
+        }
+        let cells = match self {
+            Pattern::Literal(cells) => pad(cells, 0, width)?, 
+            Pattern::Single => pad(&[1], (width - 1) / 2, width)?, 
+            Pattern::Center(cells) => pad(cells, width.saturating_sub(cells.len()) / 2, width)?, 
+            Pattern::Repeat(cells) => cells.iter()
+                .copied()
+                .cycle()
+                .take(width)
+                .collect(), 
+            Pattern::Random(density) => {
+                let width = width.try_into().map_err(|_| "Initial configuration is too wide")?;
+                return Ok(Cells::new_random_states(width, states, *density, rng))
+            }
+        };
+        match cells.iter().all(|&cell| cell < states) {
+            true => Ok(Cells(cells)), 
+            false => Err("Initial configuration has cells in states the rule doesn't have"), 
+        }
+    }
+}
+
+/// Places `cells` at `offset` in a configuration of dead cells of the given width. 
+fn pad(cells: &[u8], offset: usize, width: usize) -> Result<Vec<u8>, &'static str> {
+    if offset + cells.len() > width {
+        return Err("Pattern is wider than the given width")
+    }
+    let cells = iter::repeat_n(0, offset)
+        .chain(cells.iter().copied())
+        .chain(iter::repeat(0))
+        .take(width)
+        .collect();
+    Ok(cells)
+}
+
+impl FromStr for Pattern {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<Pattern, &'static str> {
+        let pattern = match string.split_once(':') {
+            _ if string == "single" => Pattern::Single, 
+            Some(("center", cells)) => Pattern::Center(parse_cells(cells)?), 
+            Some(("repeat", cells)) => match parse_cells(cells)? {
+                cells if cells.is_empty() => return Err("Repeated pattern must not be empty"), 
+                cells => Pattern::Repeat(cells), 
+            }, 
+            Some(("random", density)) => match density.parse() {
+                Ok(density) if (0.0..=1.0).contains(&density) => Pattern::Random(density), 
+                _ => return Err("Density of a random pattern must be a number in 0.0..=1.0"), 
+            }, 
+            _ => Pattern::Literal(parse_cells(string)?), 
+        };
+        Ok(pattern)
+    }
+}
+
+/// Parses the cells of a pattern in literal, run-length, hex or base64 form, failing as soon as it gets wider
+/// than [`MAX_WIDTH`]. 
+fn parse_cells(string: &str) -> Result<Vec<u8>, &'static str> {
+    if let Some(hex) = string.strip_prefix("hex:").or(string.strip_prefix("0x")) {
+        let digits = hex.chars()
+            .map(|char| char.to_digit(16))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("Hex pattern must only contain hex digits")?;
+        if digits.len() * 4 > MAX_WIDTH {
+            return Err(TOO_WIDE)
+        }
+        return Ok(bits(digits, 4))
+    }
+    if let Some(base64) = string.strip_prefix("base64:").or(string.strip_prefix("b64:")) {
+        let bytes = STANDARD.decode(base64).map_err(|_| "Invalid base64 pattern")?;
+        if bytes.len() * 8 > MAX_WIDTH {
+            return Err(TOO_WIDE)
+        }
+        return Ok(bits(bytes.into_iter().map(u32::from), 8))
+    }
+    let mut cells = Vec::new();
+    for run in string.split(',') {
+        let (count, run) = match run.split_once('*') {
+            Some((count, run)) => (count.parse::<usize>().map_err(|_| "Run count must be a number")?, run), 
+            None => (1, run), 
+        };
+        let run: Vec<u8> = run.chars()
+            .map(|char| char.to_digit(10).map(|digit| digit as u8))
+            .collect::<Option<_>>()
+            .ok_or("Initial configuration must only contain digits")?;
+        count
+            .checked_mul(run.len())
+            .and_then(|width| width.checked_add(cells.len()))
+            .filter(|&width| width <= MAX_WIDTH)
+            .ok_or(TOO_WIDE)?;
+        for _ in 0..count {
+            cells.extend_from_slice(&run);
+        }
+    }
+    Ok(cells)
+}
+
+/// Expands each value into its `n` least significant bits, most significant first. 
+fn bits(values: impl IntoIterator<Item = u32>, n: u32) -> Vec<u8> {
+    values.into_iter()
+        .flat_map(|value| (0..n).rev().map(move |i| (value >> i & 1) as u8))
+        .collect()
+}
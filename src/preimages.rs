@@ -0,0 +1,181 @@
+use crate::{Cells, EdgeHandling, Rule, Settings};
+
+/// The preimages of a configuration, i.e. the configurations that [`step`](crate::step) maps onto it. 
+///
+/// Preimages are counted with the transfer-matrix method: walking along the cells, only the last two cells
+/// of a partial preimage affect which cells may follow, so it suffices to count the partial preimages ending
+/// in each of the 4 pairs of cells. This takes time linear in the width rather than exponential. The same
+/// counts guide the enumeration, which never follows a partial preimage that can't be completed. 
+///
+/// ```
+/// use eca_explorer::{Cells, EdgeHandling, Preimages, Rule, Settings};
+///
+/// let settings = Settings::new(Rule(90), EdgeHandling::Crop);
+/// let target: Cells = "0110".parse().unwrap();
+/// let preimages = Preimages::new(&target, &settings);
+/// // rule 90 with cropped edges is invertible on an even number of cells
+/// assert_eq!(preimages.count(), 1);
+/// assert_eq!(preimages.iter().collect::<Vec<_>>(), vec!["1001".parse().unwrap()]);
+///
+/// // under rule 32, a cell is only alive if it was dead and both its neighbours were alive, so two
+/// // neighbouring cells can't both be alive
+/// let target: Cells = "01100".parse().unwrap();
+/// let preimages = Preimages::new(&target, &Settings::new(Rule(32), EdgeHandling::Wrap));
+/// assert!(preimages.is_garden_of_eden());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Preimages {
+    target: Vec<bool>, 
+    rule: Rule, 
+    edge_handling: EdgeHandling, 
+    /// For each choice of the first two cells, the number of ways to complete the preimage given the pair of
+    /// cells ending at each position. Indexed by the first two cells, then position, then the pair. 
+    completions: [Vec<[u128; 4]>; 4], 
+}
+
+impl Preimages {
+    /// Counts the preimages of `target` under the rule and edge handling of `settings`. 
+    ///
+    /// # Panics
+    ///
+    /// If the rule has more than two states or a radius above 1, or if `target` has cells in states other
+    /// than `0` and `1`. 
+    pub fn new(target: &Cells, settings: &Settings) -> Preimages {
+        assert!(target.max_state() <= 1, "preimages are only counted for two states");
+        let rule = settings.rule
+            .elementary()
+            .expect("preimages are only counted for rules with two states and radius 1");
+        let mut preimages = Preimages {
+            target: target.0.iter().map(|&cell| cell != 0).collect(), 
+            rule, 
+            edge_handling: settings.edge_handling, 
+            completions: Default::default(), 
+        };
+        preimages.completions = [0, 1, 2, 3].map(|start| preimages.completions(pair(start)));
+        preimages
+    }
+
+    /// Number of preimages, saturating at [`u128::MAX`] for very wide configurations. 
+    pub fn count(&self) -> u128 {
+        (0..4)
+            .filter(|&start| self.valid_start(pair(start)))
+            .fold(0, |count: u128, start| count.saturating_add(self.completions[start][1][start]))
+    }
+
+    /// Whether the configuration has no preimage, and so can only occur as an initial configuration. 
+    pub fn is_garden_of_eden(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// All preimages, in lexicographic order. 
+    pub fn iter(&self) -> impl Iterator<Item = Cells> + '_ {
+        let mut start = 0;
+        let mut current: Option<Vec<bool>> = None;
+        std::iter::from_fn(move || {
+            current = match current.take() {
+                Some(cells) => self.next(cells), 
+                None => None, 
+            };
+            // try the next choice of the first two cells once the current one is exhausted
+            while current.is_none() && start < 4 {
+                let [first, second] = pair(start);
+                if self.valid_start([first, second]) && self.completions[start][1][start] > 0 {
+                    current = Some(self.complete(start, vec![first, second]));
+                }
+                start += 1;
+            }
+            current.clone().map(|cells| Cells(cells.into_iter().map(u8::from).collect()))
+        })
+    }
+
+    /// Counts the completions of a preimage starting with the pair of cells `start`, going backwards from
+    /// the last cell. 
+    fn completions(&self, start: [bool; 2]) -> Vec<[u128; 4]> {
+        let width = self.target.len();
+        let mut completions = vec![[0; 4]; width];
+        // the pair at the last position is the last two cells
+        for (index, [left, centre]) in (0..4).map(|index| (index, pair(index))) {
+            if self.valid_end([left, centre], start) {
+                completions[width - 1][index] = 1;
+            }
+        }
+        for position in (1..width - 1).rev() {
+            for (index, [left, centre]) in (0..4).map(|index| (index, pair(index))) {
+                completions[position][index] = [false, true]
+                    .into_iter()
+                    .filter(|&right| self.rule.apply([left, centre, right]) == self.target[position])
+                    .fold(0, |count: u128, right| {
+                        count.saturating_add(completions[position + 1][index_of([centre, right])])
+                    });
+            }
+        }
+        completions
+    }
+
+    /// Whether the first two cells of a preimage produce the first cell of the target. 
+    fn valid_start(&self, [first, second]: [bool; 2]) -> bool {
+        match self.edge_handling {
+            EdgeHandling::Copy => first == self.target[0], 
+            EdgeHandling::Crop => self.rule.apply([false, first, second]) == self.target[0], 
+            // depends on the last cell, so it's checked at the end instead
+            EdgeHandling::Wrap => true, 
+        }
+    }
+
+    /// Whether the last two cells of a preimage produce the last cell of the target, and for wrapping edges, 
+    /// also the first. 
+    fn valid_end(&self, [left, last]: [bool; 2], [first, second]: [bool; 2]) -> bool {
+        let target_last = *self.target.last().unwrap();
+        match self.edge_handling {
+            EdgeHandling::Copy => last == target_last, 
+            EdgeHandling::Crop => self.rule.apply([left, last, false]) == target_last, 
+            EdgeHandling::Wrap => {
+                self.rule.apply([left, last, first]) == target_last
+                    && self.rule.apply([last, first, second]) == self.target[0]
+            }
+        }
+    }
+
+    /// Whether the cell after the pair `[left, centre]` ending at `position` can be `right`, such that the
+    /// preimage can still be completed. 
+    fn viable(&self, start: usize, position: usize, [left, centre]: [bool; 2], right: bool) -> bool {
+        self.rule.apply([left, centre, right]) == self.target[position]
+            && self.completions[start][position + 1][index_of([centre, right])] > 0
+    }
+
+    /// Completes a viable partial preimage with the smallest possible cells. 
+    fn complete(&self, start: usize, mut cells: Vec<bool>) -> Vec<bool> {
+        while cells.len() < self.target.len() {
+            let position = cells.len() - 1;
+            let pair = [cells[position - 1], cells[position]];
+            let right = !self.viable(start, position, pair, false);
+            cells.push(right);
+        }
+        cells
+    }
+
+    /// The next preimage with the same first two cells, if any. 
+    fn next(&self, mut cells: Vec<bool>) -> Option<Vec<bool>> {
+        let start = index_of([cells[0], cells[1]]);
+        // find the last cell that can be changed from dead to alive
+        while cells.len() > 2 {
+            let right = cells.pop().unwrap();
+            let position = cells.len() - 1;
+            let pair = [cells[position - 1], cells[position]];
+            if !right && self.viable(start, position, pair, true) {
+                cells.push(true);
+                return Some(self.complete(start, cells))
+            }
+        }
+        None
+    }
+}
+
+/// The pair of cells with the given index, with the left cell as the most significant bit. 
+fn pair(index: usize) -> [bool; 2] {
+    [index & 2 != 0, index & 1 != 0]
+}
+
+fn index_of([left, right]: [bool; 2]) -> usize {
+    (left as usize) << 1 | right as usize
+}
@@ -0,0 +1,118 @@
+use crate::Rule;
+
+/// Symmetries and properties of rules. 
+///
+/// Of the 256 rules, only 88 are inequivalent under left-right reflection and 0/1 complement. 
+///
+/// ```
+/// use eca_explorer::Rule;
+///
+/// assert_eq!(Rule(30).mirror(), Rule(86));
+/// assert_eq!(Rule(30).complement(), Rule(135));
+/// assert_eq!(Rule(30).mirror_complement(), Rule(149));
+/// assert_eq!(Rule(149).canonical(), Rule(30));
+/// assert_eq!((0..=255).filter(|&code| Rule(code).is_canonical()).count(), 88);
+/// ```
+impl Rule {
+    /// All rules. 
+    pub fn all() -> impl Iterator<Item = Rule> {
+        (0..=255).map(Rule)
+    }
+
+    /// The rule mapping each neighbourhood to the output of its reflection, i.e. the rule evolving
+    /// configurations as mirror images. 
+    pub fn mirror(self) -> Rule {
+        self.map(|[left, centre, right]| self.apply([right, centre, left]))
+    }
+
+    /// The rule evolving configurations with every cell inverted. 
+    pub fn complement(self) -> Rule {
+        self.map(|neighbourhood| !self.apply(neighbourhood.map(|cell| !cell)))
+    }
+
+    /// The reflection of the complement. 
+    pub fn mirror_complement(self) -> Rule {
+        self.mirror().complement()
+    }
+
+    /// The rule itself, its mirror, complement and mirror complement, in that order. These may coincide. 
+    pub fn equivalents(self) -> [Rule; 4] {
+        [self, self.mirror(), self.complement(), self.mirror_complement()]
+    }
+
+    /// The representative of the rule's equivalence class, which is the equivalent rule with the smallest
+    /// Wolfram code. 
+    pub fn canonical(self) -> Rule {
+        self.equivalents()
+            .into_iter()
+            .min_by_key(|rule| rule.0)
+            .unwrap()
+    }
+
+    pub fn is_canonical(self) -> bool {
+        self.canonical() == self
+    }
+
+    /// Whether the rule is left-right symmetric, i.e. equal to its mirror. 
+    pub fn is_symmetric(self) -> bool {
+        self.mirror() == self
+    }
+
+    /// Whether the rule is equal to its complement. 
+    pub fn is_self_complementary(self) -> bool {
+        self.complement() == self
+    }
+
+    /// Whether the rule is additive, i.e. a sum modulo 2 of some of the three neighbours. The superposition
+    /// principle holds for additive rules: evolving the XOR of two configurations yields the XOR of their
+    /// evolutions. 
+    ///
+    /// ```
+    /// use eca_explorer::Rule;
+    ///
+    /// let additive: Vec<u8> = Rule::all().filter(|rule| rule.is_additive()).map(|rule| rule.0).collect();
+    /// assert_eq!(additive, [0, 60, 90, 102, 150, 170, 204, 240]);
+    /// ```
+    pub fn is_additive(self) -> bool {
+        let output = |n: u8| self.0 >> n & 1;
+        (0..8).all(|a| (0..8).all(|b| output(a ^ b) == output(a) ^ output(b)))
+    }
+
+    /// Whether the output only depends on the number of alive cells in the neighbourhood. 
+    pub fn is_totalistic(self) -> bool {
+        let sum = |n: u8| n.count_ones();
+        (0..8).all(|a| (0..8).all(|b| sum(a) != sum(b) || self.0 >> a & 1 == self.0 >> b & 1))
+    }
+
+    /// Whether the number of alive cells is conserved on any ring of cells (i.e. with
+    /// [`EdgeHandling::Wrap`](crate::EdgeHandling::Wrap)). Such rules can be seen as particles moving
+    /// around, as in the traffic rule 184. 
+    ///
+    /// ```
+    /// use eca_explorer::Rule;
+    ///
+    /// let conserving: Vec<u8> = Rule::all()
+    ///     .filter(|rule| rule.is_number_conserving())
+    ///     .map(|rule| rule.0)
+    ///     .collect();
+    /// assert_eq!(conserving, [170, 184, 204, 226, 240]);
+    /// ```
+    pub fn is_number_conserving(self) -> bool {
+        // rings of up to 4 cells suffice to rule out every radius-1 rule that isn't number-conserving
+        (1..=4).all(|width| (0..1u8 << width).all(|ring| {
+            let cell = |i: usize| ring >> (i % width) & 1 != 0;
+            let next = (0..width)
+                .filter(|&i| self.apply([cell(i + width - 1), cell(i), cell(i + 1)]))
+                .count();
+            next == ring.count_ones() as usize
+        }))
+    }
+
+    /// The rule whose output for each neighbourhood is given by `f`. 
+    fn map(self, f: impl Fn([bool; 3]) -> bool) -> Rule {
+        let code = (0..8)
+            .filter(|n| f([n & 4 != 0, n & 2 != 0, n & 1 != 0]))
+            .fold(0, |code, n| code | 1 << n);
+        Rule(code)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::Cells;
+
+/// Statistics of a single generation. 
+///
+/// ```
+/// use eca_explorer::{Cells, Statistics};
+///
+/// let previous: Cells = "00110110".parse().unwrap();
+/// let cells: Cells = "01110100".parse().unwrap();
+/// let statistics = Statistics::new(&cells, Some(&previous), 2);
+/// assert_eq!(statistics.density, 0.5);
+/// // half the cells are alive, so single cells carry a full bit of information
+/// assert_eq!(statistics.entropies[0], 1.0);
+/// assert_eq!(statistics.runs, 2);
+/// assert_eq!(statistics.mean_run_length, 2.0);
+/// assert_eq!(statistics.hamming_distance, Some(2));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    /// Fraction of live cells. 
+    pub density: f64, 
+    /// Block entropies in bits for block sizes 1, 2, etc. 
+    pub entropies: Vec<f64>, 
+    /// Number of runs of consecutive live cells. 
+    pub runs: usize, 
+    /// Mean length of the runs of live cells, or 0 if there are none. 
+    pub mean_run_length: f64, 
+    /// Number of cells that differ from the previous generation, if given. 
+    pub hamming_distance: Option<usize>, 
+}
+
+impl Statistics {
+    /// Computes the statistics of `cells`, with block entropies for block sizes 1 to `max_block_size`. 
+    pub fn new(cells: &Cells, previous: Option<&Cells>, max_block_size: usize) -> Statistics {
+        let width = cells.0.len();
+        let alive = cells.0.iter().filter(|&&cell| cell != 0).count();
+        let runs = cells.0
+            .iter()
+            .enumerate()
+            .filter(|&(i, &cell)| cell != 0 && (i == 0 || cells.0[i - 1] == 0))
+            .count();
+        let mean_run_length = match runs {
+            0 => 0.0, 
+            runs => alive as f64 / runs as f64, 
+        };
+        let hamming_distance = previous.map(|previous| {
+            assert_eq!(previous.0.len(), width);
+            previous.0
+                .iter()
+                .zip(&cells.0)
+                .filter(|(previous, cell)| previous != cell)
+                .count()
+        });
+        Statistics {
+            density: alive as f64 / width as f64, 
+            entropies: (1..=max_block_size).map(|size| block_entropy(cells, size)).collect(), 
+            runs, 
+            mean_run_length, 
+            hamming_distance, 
+        }
+    }
+}
+
+/// The Shannon entropy in bits of the distribution of blocks of `size` consecutive cells. The blocks start
+/// at every cell and wrap around the edges, so there are as many blocks as cells. Cells may be in any
+/// state, so with more than two states a single cell can carry more than one bit. 
+///
+/// ```
+/// use eca_explorer::{block_entropy, Cells};
+///
+/// let cells: Cells = "0101".parse().unwrap();
+/// assert_eq!(block_entropy(&cells, 1), 1.0);
+/// // only 01 and 10 occur
+/// assert_eq!(block_entropy(&cells, 2), 1.0);
+/// ```
+pub fn block_entropy(cells: &Cells, size: usize) -> f64 {
+    assert!((1..=16).contains(&size), "block size must be between 1 and 16");
+    let width = cells.0.len();
+    // states are digits, so even 16 of them fit in a `u64`
+    let mut blocks: Vec<u64> = (0..width)
+        .map(|start| (start..start + size).fold(0, |block, i| block * 10 + u64::from(cells.0[i % width])))
+        .collect();
+    blocks.sort_unstable();
+    blocks
+        .chunk_by(|a, b| a == b)
+        .map(|chunk| chunk.len())
+        .map(|count| {
+            let probability = count as f64 / width as f64;
+            -probability * probability.log2()
+        })
+        .sum()
+}
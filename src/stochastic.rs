@@ -0,0 +1,92 @@
+use std::{cell::{RefCell, RefMut}, str::FromStr};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use crate::Rule;
+
+/// Probabilistic rule giving the probability that a cell becomes alive for each of the 8 neighbourhoods, 
+/// from `000` to `111`. Deterministic rules are the special case of probabilities of only `0` and `1`. 
+///
+/// ```
+/// use eca_explorer::ProbabilityTable;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// // rule 30 with the neighbourhood `001` only leading to a live cell half of the time
+/// let table: ProbabilityTable = "0,0.5,1,1,1,0,0,0".parse().unwrap();
+/// let mut rng = StdRng::seed_from_u64(0);
+/// assert!(table.apply([false, true, false], &mut rng));
+/// assert!(!table.apply([true, true, true], &mut rng));
+/// assert!("0,0.5,1".parse::<ProbabilityTable>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProbabilityTable(pub [f64; 8]);
+
+impl ProbabilityTable {
+    /// Draws the new value of a cell with the probability for its neighbourhood, indexed like the bits of a
+    /// Wolfram code. 
+    pub fn apply(&self, neighborhood: [bool; 3], rng: &mut impl Rng) -> bool {
+        let [left, centre, right] = neighborhood.map(usize::from);
+        rng.gen_bool(self.0[left << 2 | centre << 1 | right])
+    }
+}
+
+impl FromStr for ProbabilityTable {
+    type Err = &'static str;
+
+    /// Parses 8 comma-separated probabilities, for the neighbourhoods `000` to `111`. 
+    fn from_str(string: &str) -> Result<ProbabilityTable, &'static str> {
+        let probabilities = string
+            .split(',')
+            .map(|probability| probability.trim().parse::<f64>().ok().filter(|p| (0.0..=1.0).contains(p)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("Probabilities must be numbers in 0.0..=1.0")?;
+        probabilities
+            .try_into()
+            .map(ProbabilityTable)
+            .map_err(|_| "A probability table must have 8 entries, one per neighbourhood")
+    }
+}
+
+/// Randomness in how cells are updated. The random numbers are drawn from a seeded generator, so that runs
+/// can be replayed; clones of it draw the same numbers as the original from then on. 
+#[derive(Clone, Debug)]
+pub struct Stochastic {
+    /// Probability that each computed cell is flipped after applying the rule. With more than two states, 
+    /// a flipped cell changes to one of the other states at random. 
+    pub noise: f64, 
+    /// Probabilistic rule replacing the rule of the settings, which must be elementary. 
+    pub table: Option<ProbabilityTable>, 
+    rng: RefCell<StdRng>, 
+}
+
+impl Stochastic {
+    pub fn new(noise: f64, table: Option<ProbabilityTable>, seed: u64) -> Stochastic {
+        Stochastic {
+            noise, 
+            table, 
+            rng: RefCell::new(StdRng::seed_from_u64(seed)), 
+        }
+    }
+
+    /// The random number generator. 
+    fn rng(&self) -> RefMut<'_, StdRng> {
+        self.rng.borrow_mut()
+    }
+
+    /// Applies the probability table, or else the elementary `rule`, to a neighbourhood, then adds noise. 
+    pub(crate) fn apply(&self, rule: Rule, neighborhood: [bool; 3]) -> bool {
+        let rng = &mut *self.rng();
+        let cell = match &self.table {
+            Some(table) => table.apply(neighborhood, rng), 
+            None => rule.apply(neighborhood), 
+        };
+        cell ^ rng.gen_bool(self.noise)
+    }
+
+    /// Adds noise to a cell computed by a rule with the given number of states. 
+    pub(crate) fn perturb(&self, cell: u8, states: u8) -> u8 {
+        let rng = &mut *self.rng();
+        match rng.gen_bool(self.noise) {
+            true => (cell + rng.gen_range(1..states)) % states, 
+            false => cell, 
+        }
+    }
+}
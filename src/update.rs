@@ -0,0 +1,149 @@
+use std::str::FromStr;
+use rand::Rng;
+use crate::{Cells, EdgeHandling, Rule, Settings, Storage};
+
+/// Scheme by which the cells of a generation are updated. Except for [`Update::Synchronous`], cells are
+/// updated in place, so that later updates see the earlier ones. Parsed from `synchronous`, `sweep`, 
+/// `random:<cells>`, `block:<size>` or `async:<probability>`. 
+///
+/// ```
+/// use eca_explorer::{Automaton, Cells, EdgeHandling, Rule, Settings, Update};
+///
+/// // sweeping rule 60 (each cell becomes the XOR of itself and its left neighbour) from left to right
+/// // carries a single live cell all the way to the right edge in one generation
+/// let mut settings = Settings::new(Rule(60), EdgeHandling::Crop);
+/// settings.update = "sweep".parse().unwrap();
+/// let mut automaton = Automaton::new("1000000".parse::<Cells>().unwrap(), settings);
+/// assert_eq!(automaton.advance(), &"1111111".parse::<Cells>().unwrap());
+/// assert!("block:0".parse::<Update>().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Update {
+    /// All cells at once, from the previous generation. 
+    Synchronous, 
+    /// The given number of cells chosen at random, possibly more than once, one after another. 
+    RandomSequential(usize), 
+    /// All cells one after another, from left to right. 
+    Sweep, 
+    /// Blocks of the given number of neighbouring cells one after another from left to right, with the
+    /// cells of each block updated at once. 
+    BlockSequential(usize), 
+    /// Each cell with the given probability α, all at once. 
+    Asynchronous(f64), 
+}
+
+impl Update {
+    /// Whether the cells updated are chosen at random, with the generator seeded by
+    /// [`Settings::with_update`]. 
+    pub fn is_random(&self) -> bool {
+        matches!(self, Update::RandomSequential(_) | Update::Asynchronous(_))
+    }
+}
+
+impl FromStr for Update {
+    type Err = &'static str;
+
+    fn from_str(string: &str) -> Result<Update, &'static str> {
+        let update = match string.split_once(':') {
+            _ if string == "synchronous" => Update::Synchronous, 
+            _ if string == "sweep" => Update::Sweep, 
+            Some(("random", cells)) => match cells.parse() {
+                Ok(cells) => Update::RandomSequential(cells), 
+                Err(_) => return Err("Number of randomly updated cells must be a number"), 
+            }, 
+            Some(("block", size)) => match size.parse() {
+                Ok(size) if size > 0 => Update::BlockSequential(size), 
+                _ => return Err("Block size must be a positive number"), 
+            }, 
+            Some(("async", alpha)) => match alpha.parse() {
+                Ok(alpha) if (0.0..=1.0).contains(&alpha) => Update::Asynchronous(alpha), 
+                _ => return Err("Update probability must be a number in 0.0..=1.0"), 
+            }, 
+            _ => return Err(concat!(
+                "Update scheme must be `synchronous`, `sweep`, `random:<cells>`, `block:<size>` or ", 
+                "`async:<probability>`", 
+            )), 
+        };
+        Ok(update)
+    }
+}
+
+impl Cells {
+    /// Computes the next generation of `self` into `next` under the [update scheme](Settings::update), 
+    /// setting `updated` to whether each cell was updated. 
+    ///
+    /// # Panics
+    ///
+    /// If the rule is second-order and the scheme isn't synchronous. 
+    pub fn update_into(&self, next: &mut Cells, settings: &Settings, updated: &mut Vec<bool>) {
+        let width = self.0.len();
+        updated.clear();
+        updated.resize(width, true);
+        if let Update::Synchronous = settings.update {
+            return self.step_into(next, settings)
+        }
+        assert!(!settings.second_order, "only synchronous updates are supported for second-order rules");
+        next.0.clone_from(&self.0);
+        let rules = settings.elementary_rules();
+        let next_cell = |cells: &Cells, index| cells.next_cell(index, settings, rules.as_ref());
+
+        match settings.update {
+            Update::Synchronous => unreachable!(), 
+            Update::RandomSequential(cells) => {
+                updated.fill(false);
+                for _ in 0..cells {
+                    let i = settings.update_rng.borrow_mut().gen_range(0..width);
+                    next.0[i] = next_cell(next, i);
+                    updated[i] = true;
+                }
+            }
+            Update::Sweep => for i in 0..width {
+                next.0[i] = next_cell(next, i);
+            }
+            Update::BlockSequential(size) => for start in (0..width).step_by(size) {
+                let block: Vec<u8> = (start..width.min(start + size))
+                    .map(|i| next_cell(next, i))
+                    .collect();
+                next.0[start..][..block.len()].copy_from_slice(&block);
+            }
+            Update::Asynchronous(alpha) => {
+                let rng = &mut *settings.update_rng.borrow_mut();
+                for cell in updated.iter_mut() {
+                    *cell = rng.gen_bool(alpha);
+                }
+                for i in (0..width).filter(|&i| updated[i]) {
+                    next.0[i] = next_cell(self, i);
+                }
+            }
+        }
+    }
+
+    /// The new value of the cell at `index`, as it would be computed by a synchronous step, given the
+    /// [elementary rules](Settings::elementary_rules) of the settings. 
+    fn next_cell(&self, index: usize, settings: &Settings, rules: Option<&impl Fn(usize) -> Rule>) -> u8 {
+        let rule = settings.rule;
+        let radius = rule.radius() as isize;
+        let width = self.0.len() as isize;
+        let i = index as isize;
+        let inside = i >= radius && i + radius < width;
+        if !inside && settings.edge_handling == EdgeHandling::Copy {
+            return self.0[index]
+        }
+        let neighbourhood: Vec<u8> = (i - radius..=i + radius)
+            .map(|index| match ((0..width).contains(&index), settings.edge_handling) {
+                (true, _) => self.0[index as usize], 
+                (false, EdgeHandling::Wrap) => self.0[index.rem_euclid(width) as usize], 
+                (false, _) => 0, 
+            })
+            .collect();
+        match (&settings.stochastic, rules.map(|rules| rules(index))) {
+            (Some(stochastic), Some(elementary)) => {
+                let neighbourhood = [0, 1, 2].map(|i| neighbourhood[i] != 0);
+                u8::from(stochastic.apply(elementary, neighbourhood))
+            }
+            (None, Some(elementary)) => u8::from(elementary.apply([0, 1, 2].map(|i| neighbourhood[i] != 0))), 
+            (Some(stochastic), None) => stochastic.perturb(rule.apply(&neighbourhood), rule.states()), 
+            (None, None) => rule.apply(&neighbourhood), 
+        }
+    }
+}
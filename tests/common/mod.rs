@@ -0,0 +1,24 @@
+//! Helpers shared by the integration tests comparing runs of whole configurations. 
+
+// each test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use eca_explorer::{step, Cells, EdgeHandling, Settings, Storage};
+use rand::{rngs::StdRng, Rng};
+
+pub const WIDTH: usize = 70;
+pub const GENERATIONS: usize = 16;
+pub const EDGE_HANDLINGS: [EdgeHandling; 3] = [EdgeHandling::Copy, EdgeHandling::Crop, EdgeHandling::Wrap];
+
+pub fn random_cells(rng: &mut StdRng) -> Cells {
+    Cells((0..WIDTH).map(|_| u8::from(rng.gen::<bool>())).collect())
+}
+
+/// Runs `front` for [`GENERATIONS`] generations. 
+pub fn run<C: Storage>(mut front: C, settings: &Settings) -> C {
+    let mut back = front.clone();
+    for _ in 0..GENERATIONS {
+        (front, back) = step(front, back, settings);
+    }
+    front
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use eca_explorer::{Cells, EdgeHandling, Family, LocalRule, PackedCells, Rule, Settings};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use common::{random_cells, run, WIDTH};
+
+/// An elementary rule of radius 2 ignoring the outermost cells evolves like the rule of radius 1, except for
+/// the extra edge cells kept with `EdgeHandling::Copy`. 
+#[test]
+fn wide_step_matches_elementary_step() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for rule in Rule::all() {
+        let code = (0..32_u64)
+            .filter(|n| rule.0 >> (n >> 1 & 7) & 1 != 0)
+            .fold(0, |code, n| code | 1 << n);
+        let wide = LocalRule::new(Family::Elementary, 2, 2, code).unwrap();
+
+        for edge_handling in [EdgeHandling::Crop, EdgeHandling::Wrap] {
+            let initial = random_cells(&mut rng);
+            assert_eq!(
+                run(initial.clone(), &Settings::new(wide, edge_handling)), 
+                run(initial, &Settings::new(rule, edge_handling)), 
+                "rule {}, {edge_handling:?}", rule.0, 
+            );
+        }
+    }
+}
+
+#[test]
+fn totalistic_rules_of_radius_1_are_elementary() {
+    // 0b0110 is alive with one or two live cells, i.e. rule 126
+    let rule = LocalRule::new(Family::Totalistic, 2, 1, 0b0110).unwrap();
+    assert_eq!(rule.elementary(), Some(Rule(126)));
+    // 0b011000 is alive with two live neighbours around a dead cell, or one around a live cell
+    let rule = LocalRule::new(Family::OuterTotalistic, 2, 1, 0b011000).unwrap();
+    assert_eq!(rule.elementary(), Some(Rule(104)));
+}
+
+#[test]
+fn packed_wide_step_matches_wide_step() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for family in [Family::Totalistic, Family::OuterTotalistic] {
+        for edge_handling in [EdgeHandling::Copy, EdgeHandling::Crop, EdgeHandling::Wrap] {
+            let rule = LocalRule::new(family, 2, 3, rng.gen_range(0..1 << 8)).unwrap();
+            let settings = Settings::new(rule, edge_handling);
+            let initial = random_cells(&mut rng);
+            assert_eq!(
+                Cells::from(&run(PackedCells::from(&initial), &settings)), 
+                run(initial, &settings), 
+                "{rule}, {edge_handling:?}", 
+            );
+        }
+    }
+}
+
+/// A totalistic rule with three states evolves like the elementary rule whose entry for each neighbourhood is
+/// the totalistic entry for its sum. 
+#[test]
+fn totalistic_rules_match_elementary_rules_with_more_states() {
+    let mut rng = StdRng::seed_from_u64(2);
+    for _ in 0..16 {
+        let totalistic = LocalRule::new(Family::Totalistic, 3, 1, rng.gen_range(0..3_u64.pow(7))).unwrap();
+        let code = (0..27_u32)
+            .map(|n| u64::from(totalistic.apply(&[n / 9 % 3, n / 3 % 3, n % 3].map(|cell| cell as u8))))
+            .rev()
+            .fold(0, |code, digit| code * 3 + digit);
+        let elementary = LocalRule::new(Family::Elementary, 3, 1, code).unwrap();
+
+        for edge_handling in [EdgeHandling::Copy, EdgeHandling::Crop, EdgeHandling::Wrap] {
+            let initial = Cells((0..WIDTH).map(|_| rng.gen_range(0..3)).collect());
+            assert_eq!(
+                run(initial.clone(), &Settings::new(totalistic, edge_handling)), 
+                run(initial, &Settings::new(elementary, edge_handling)), 
+                "{totalistic}, {edge_handling:?}", 
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use std::{env, fs, process::{self, Command}};
+use eca_explorer::{Cells, EdgeHandling, Hybrid, PackedCells, Rule, Settings, Storage};
+use rand::{rngs::StdRng, SeedableRng};
+use common::{random_cells, run, EDGE_HANDLINGS};
+
+/// A hybrid automaton with the same rule for every cell evolves like that rule. 
+#[test]
+fn uniform_hybrid_matches_rule() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for rule in Rule::all() {
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let mut settings = Settings::new(Rule(0), edge_handling);
+            settings.hybrid = Some(Hybrid(vec![rule]));
+            let expected = run(initial.clone(), &Settings::new(rule, edge_handling));
+            assert_eq!(run(initial, &settings), expected, "rule {}, {edge_handling:?}", rule.0);
+        }
+    }
+}
+
+/// Each cell of the `90,150` hybrid applies its own rule to its neighbourhood, including the cells at both
+/// edges, whichever rule the last cell gets. 
+#[test]
+fn alternating_hybrid_applies_rule_of_each_cell() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let mut settings = Settings::new(Rule(0), EdgeHandling::Wrap);
+    settings.hybrid = Some("90,150".parse().unwrap());
+    for width in [69, 70] {
+        for edge_handling in EDGE_HANDLINGS {
+            settings.edge_handling = edge_handling;
+            let mut cells = random_cells(&mut rng);
+            cells.0.truncate(width);
+            let mut next = cells.clone();
+            cells.step_into(&mut next, &settings);
+
+            let width = width as isize;
+            let cell = |index: isize| match ((0..width).contains(&index), edge_handling) {
+                (true, _) | (false, EdgeHandling::Wrap) => cells.0[index.rem_euclid(width) as usize] != 0, 
+                (false, _) => false, 
+            };
+            for i in 0..width {
+                let rule = match i % 2 {
+                    0 => Rule(90), 
+                    _ => Rule(150), 
+                };
+                let expected = match (i == 0 || i == width - 1, edge_handling) {
+                    (true, EdgeHandling::Copy) => cell(i), 
+                    _ => rule.apply([cell(i - 1), cell(i), cell(i + 1)]), 
+                };
+                assert_eq!(next.0[i as usize] != 0, expected, "width {width}, {edge_handling:?}, cell {i}");
+            }
+        }
+    }
+}
+
+/// Packed cells fall back to applying the rule of each cell. 
+#[test]
+fn packed_hybrid_matches_hybrid() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let hybrids = ["90,150", "30,3*110,45", "90,150,150,90,60"];
+    for hybrid in hybrids {
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let mut settings = Settings::new(Rule(0), edge_handling);
+            settings.hybrid = Some(hybrid.parse().unwrap());
+            let expected = run(initial.clone(), &settings);
+            let packed = run(PackedCells::from(&initial), &settings);
+            assert_eq!(Cells::from(&packed), expected, "hybrid {hybrid}, {edge_handling:?}");
+        }
+    }
+}
+
+/// Regions start at the left edge and end at the right one, without wrapping around, and the rules repeating
+/// only start a new region where the rule changes. 
+#[test]
+fn regions_end_at_rule_boundaries() {
+    let regions = |hybrid: &str, width| hybrid.parse::<Hybrid>().unwrap().regions(width);
+    assert_eq!(regions("90,150", 0), vec![]);
+    assert_eq!(regions("90,150", 1), vec![false]);
+    assert_eq!(regions("30", 4), vec![false; 4]);
+    assert_eq!(regions("90,150", 5), vec![false, true, false, true, false]);
+    // the last region has the rule of the first, but is a region of its own
+    assert_eq!(regions("2*90,3*150", 7), vec![false, false, true, true, true, false, false]);
+    // the repeated list continues the region of its last rule
+    assert_eq!(regions("90,150,90", 6), vec![false, true, false, false, true, false]);
+}
+
+/// The rule argument is ignored in hybrid runs, so the caption and the statistics show the hybrid rules. 
+#[test]
+fn hybrid_runs_are_labelled_by_their_rules() {
+    let directory = env::temp_dir().join(format!("eca_explorer_hybrid_{}", process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let (svg, stats) = (directory.join("hybrid.svg"), directory.join("hybrid.csv"));
+    let status = Command::new(env!("CARGO_BIN_EXE_eca_explorer"))
+        .args(["0", "single", "-w", "9", "-g", "2", "--hybrid", "90,150,150", "--caption"])
+        .arg("--svg").arg(&svg)
+        .arg("--stats").arg(&stats)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert!(fs::read_to_string(&svg).unwrap().contains(">Hybrid 90,2*150<"));
+    let stats = fs::read_to_string(&stats).unwrap();
+    assert!(stats.lines().skip(1).all(|row| row.contains(",\"hybrid 90,2*150\",")), "{stats}");
+    fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,79 @@
+use eca_explorer::{step, Cells, EdgeHandling, PackedCells, Rule, Settings};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Widths around the word boundaries, where the packed representation is most likely to go wrong. 
+const WIDTHS: [usize; 9] = [3, 4, 63, 64, 65, 100, 127, 128, 129];
+const GENERATIONS: usize = 16;
+
+fn random_cells(width: usize, rng: &mut StdRng) -> Cells {
+    Cells((0..width).map(|_| u8::from(rng.gen::<bool>())).collect())
+}
+
+#[test]
+fn packed_step_matches_step() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let edge_handlings = [EdgeHandling::Copy, EdgeHandling::Crop, EdgeHandling::Wrap];
+
+    for rule in 0..=255 {
+        for edge_handling in edge_handlings {
+            let settings = Settings::new(Rule(rule), edge_handling);
+
+            for width in WIDTHS {
+                let mut front = random_cells(width, &mut rng);
+                let mut back = front.clone();
+                let mut packed_front = PackedCells::from(&front);
+                let mut packed_back = packed_front.clone();
+
+                for generation in 0..GENERATIONS {
+                    (front, back) = step(front, back, &settings);
+                    (packed_front, packed_back) = step(packed_front, packed_back, &settings);
+                    assert_eq!(
+                        Cells::from(&packed_front), front, 
+                        "rule {rule}, {edge_handling:?}, width {width}, generation {generation}", 
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn packing_round_trips() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for width in WIDTHS {
+        let cells = random_cells(width, &mut rng);
+        let packed = PackedCells::from(&cells);
+        assert_eq!(packed.width(), width);
+        assert_eq!(Cells::from(&packed), cells);
+    }
+}
+
+#[test]
+fn packed_second_order_step_matches_step() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let edge_handlings = [EdgeHandling::Copy, EdgeHandling::Crop, EdgeHandling::Wrap];
+
+    for rule in 0..=255 {
+        for edge_handling in edge_handlings {
+            let mut settings = Settings::new(Rule(rule), edge_handling);
+            settings.second_order = true;
+
+            for width in WIDTHS {
+                // the back buffer holds the previous generation
+                let mut front = random_cells(width, &mut rng);
+                let mut back = random_cells(width, &mut rng);
+                let mut packed_front = PackedCells::from(&front);
+                let mut packed_back = PackedCells::from(&back);
+
+                for generation in 0..GENERATIONS {
+                    (front, back) = step(front, back, &settings);
+                    (packed_front, packed_back) = step(packed_front, packed_back, &settings);
+                    assert_eq!(
+                        Cells::from(&packed_front), front, 
+                        "rule {rule}, {edge_handling:?}, width {width}, generation {generation}", 
+                    );
+                }
+            }
+        }
+    }
+}
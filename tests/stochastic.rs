@@ -0,0 +1,88 @@
+mod common;
+
+use std::process::Command;
+use eca_explorer::{Cells, EdgeHandling, PackedCells, ProbabilityTable, Rule, Settings, Stochastic, Storage};
+use rand::{rngs::StdRng, SeedableRng};
+use common::{random_cells, run, EDGE_HANDLINGS};
+
+fn with_noise(rule: Rule, edge_handling: EdgeHandling, noise: f64, seed: u64) -> Settings {
+    let mut settings = Settings::new(rule, edge_handling);
+    settings.stochastic = Some(Stochastic::new(noise, None, seed));
+    settings
+}
+
+/// A probability table of only `0` and `1` without noise evolves like the rule with those outputs. 
+#[test]
+fn deterministic_table_matches_rule() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for rule in Rule::all() {
+        let table = ProbabilityTable(std::array::from_fn(|n| f64::from(rule.0 >> n & 1)));
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let mut settings = Settings::new(Rule(0), edge_handling);
+            settings.stochastic = Some(Stochastic::new(0.0, Some(table), 0));
+            let expected = run(initial.clone(), &Settings::new(rule, edge_handling));
+            assert_eq!(run(initial, &settings), expected, "rule {}, {edge_handling:?}", rule.0);
+        }
+    }
+}
+
+/// Noisy runs are replayed exactly from the same seed, also by packed cells, but not from another one. 
+#[test]
+fn same_seed_gives_same_run() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for rule in [Rule(30), Rule(90), Rule(110)] {
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let message = format!("rule {}, {edge_handling:?}", rule.0);
+            let expected = run(initial.clone(), &with_noise(rule, edge_handling, 0.1, 2));
+            assert_eq!(run(initial.clone(), &with_noise(rule, edge_handling, 0.1, 2)), expected, "{message}");
+            let packed = run(PackedCells::from(&initial), &with_noise(rule, edge_handling, 0.1, 2));
+            assert_eq!(Cells::from(&packed), expected, "{message}");
+            assert_ne!(run(initial, &with_noise(rule, edge_handling, 0.1, 3)), expected, "{message}");
+        }
+    }
+}
+
+/// Without noise every cell becomes what the rule makes of its neighbourhood, and with a noise of 1 every
+/// cell becomes the opposite. 
+#[test]
+fn noise_inverts_cells_with_its_probability() {
+    let mut rng = StdRng::seed_from_u64(2);
+    for rule in Rule::all() {
+        let cells = random_cells(&mut rng);
+        let width = cells.0.len();
+        let expected: Vec<bool> = (0..width)
+            .map(|i| rule.apply([i + width - 1, i, i + 1].map(|j| cells.0[j % width] != 0)))
+            .collect();
+        for (noise, inverted) in [(0.0, false), (1.0, true)] {
+            let mut next = cells.clone();
+            cells.step_into(&mut next, &with_noise(rule, EdgeHandling::Wrap, noise, 0));
+            let next: Vec<bool> = next.0.iter().map(|&cell| cell != 0).collect();
+            let expected: Vec<bool> = expected.iter().map(|&cell| cell ^ inverted).collect();
+            assert_eq!(next, expected, "rule {}, noise {noise}", rule.0);
+        }
+    }
+}
+
+/// A generation repeating under noise doesn't mean the run cycles, so noisy runs never report a cycle, 
+/// and can't be stopped on one. 
+#[test]
+fn noisy_runs_report_no_cycle() {
+    let explore = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_eca_explorer")).args(args).output().unwrap()
+    };
+    for randomness in [["--noise", "0.3"], ["--prob-table", "0,1,1,1,1,0,0,0.5"]] {
+        for seed in 0..8 {
+            let seed = seed.to_string();
+            let mut args = vec!["30", "random:0.5", "-w", "4", "-g", "200", "--seed", &seed, "--report"];
+            args.extend(randomness);
+            let output = explore(&args);
+            let report = String::from_utf8_lossy(&output.stderr);
+            assert!(output.status.success(), "{args:?}: {report}");
+            assert!(report.contains("Cycle: not detected"), "{args:?}: {report}");
+            args.push("--stop-on-cycle");
+            assert!(!explore(&args).status.success(), "{args:?}");
+        }
+    }
+}
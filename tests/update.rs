@@ -0,0 +1,112 @@
+mod common;
+
+use std::process::Command;
+use eca_explorer::{Automaton, Cells, EdgeHandling, Rule, Settings, Update};
+use rand::{rngs::StdRng, SeedableRng};
+use common::{random_cells, run, EDGE_HANDLINGS, WIDTH};
+
+fn with_update(rule: Rule, edge_handling: EdgeHandling, update: Update) -> Settings {
+    Settings::new(rule, edge_handling).with_update(update, 0)
+}
+
+/// Blocks of a single cell are a sweep, a block of all cells is synchronous, and so is updating every cell
+/// with probability 1. 
+#[test]
+fn degenerate_schemes_match() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for rule in Rule::all() {
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let run_with = |update| run(initial.clone(), &with_update(rule, edge_handling, update));
+            let synchronous = run(initial.clone(), &Settings::new(rule, edge_handling));
+            let message = format!("rule {}, {edge_handling:?}", rule.0);
+
+            assert_eq!(run_with(Update::BlockSequential(1)), run_with(Update::Sweep), "{message}");
+            assert_eq!(run_with(Update::BlockSequential(WIDTH)), synchronous, "{message}");
+            assert_eq!(run_with(Update::Asynchronous(1.0)), synchronous, "{message}");
+        }
+    }
+}
+
+/// Under rule 255 every updated cell comes alive, so starting from dead cells the live ones are exactly the
+/// cells marked as updated, of which there are no more than the number of cells updated at random. 
+#[test]
+fn random_sequential_marks_updated_cells() {
+    for cells in [0, 1, 5, 20, 100] {
+        for edge_handling in [EdgeHandling::Crop, EdgeHandling::Wrap] {
+            let settings = with_update(Rule(255), edge_handling, Update::RandomSequential(cells));
+            let mut automaton = Automaton::new(Cells(vec![0; WIDTH]), settings);
+            let mut updated = Vec::new();
+            let next = automaton.advance_marked(&mut updated);
+            let alive: Vec<bool> = next.0.iter().map(|&cell| cell != 0).collect();
+            let message = format!("{cells} cells, {edge_handling:?}");
+            assert_eq!(alive, updated, "{message}");
+            assert!(updated.iter().filter(|&&updated| updated).count() <= cells, "{message}");
+        }
+    }
+}
+
+/// Cells that aren't marked as updated keep their value. 
+#[test]
+fn random_sequential_keeps_other_cells() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for rule in [Rule(30), Rule(90), Rule(110)] {
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let settings = with_update(rule, edge_handling, Update::RandomSequential(20));
+            let mut automaton = Automaton::new(initial.clone(), settings);
+            let mut updated = Vec::new();
+            let next = automaton.advance_marked(&mut updated);
+            for i in (0..WIDTH).filter(|&i| !updated[i]) {
+                assert_eq!(next.0[i], initial.0[i], "rule {}, {edge_handling:?}, cell {i}", rule.0);
+            }
+        }
+    }
+}
+
+/// Updating each cell with probability 0 updates none of them. 
+#[test]
+fn asynchronous_without_updates_keeps_cells() {
+    let mut rng = StdRng::seed_from_u64(2);
+    for rule in Rule::all() {
+        for edge_handling in EDGE_HANDLINGS {
+            let initial = random_cells(&mut rng);
+            let settings = with_update(rule, edge_handling, Update::Asynchronous(0.0));
+            let mut automaton = Automaton::new(initial.clone(), settings);
+            let mut updated = Vec::new();
+            let message = format!("rule {}, {edge_handling:?}", rule.0);
+            assert_eq!(automaton.advance_marked(&mut updated), &initial, "{message}");
+            assert_eq!(updated, vec![false; WIDTH], "{message}");
+        }
+    }
+}
+
+/// Random updates are replayed from the same seed, which is 0 unless given. 
+#[test]
+fn random_updates_follow_seed() {
+    let initial = random_cells(&mut StdRng::seed_from_u64(3));
+    let settings = Settings::new(Rule(30), EdgeHandling::Wrap);
+    let run_with = |settings: Settings| run(initial.clone(), &settings);
+    for update in [Update::RandomSequential(20), Update::Asynchronous(0.5)] {
+        let mut unseeded = settings.clone();
+        unseeded.update = update;
+        assert_eq!(run_with(unseeded), run_with(settings.clone().with_update(update, 0)), "{update:?}");
+        let seeded = run_with(settings.clone().with_update(update, 7));
+        assert_eq!(run_with(settings.clone().with_update(update, 7)), seeded, "{update:?}");
+        assert_ne!(run_with(settings.clone().with_update(update, 8)), seeded, "{update:?}");
+    }
+}
+
+/// Runs with random updates never report a cycle, and runs with other updates that aren't synchronous don't
+/// report cycles that shift in space. 
+#[test]
+fn reported_cycles_follow_updates() {
+    let report = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_eca_explorer")).args(args).output().unwrap();
+        String::from_utf8(output.stderr).unwrap()
+    };
+    let random = report(&["30", "-w", "5", "-g", "200", "--update", "async:0.3", "--seed", "3", "--report"]);
+    assert!(random.contains("Cycle: not detected"), "{random}");
+    let sweep = report(&["1", "0000000", "-g", "12", "--update", "sweep", "--report"]);
+    assert!(sweep.contains("Cycle: none found"), "{sweep}");
+}